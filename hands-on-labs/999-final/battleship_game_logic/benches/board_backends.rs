@@ -0,0 +1,71 @@
+use battleship_game_logic::{
+    backtracking_placer, random_placer, BattleshipBoardContent, BitBoard, BoardFiller, BoardIndex,
+    Direction, ShipSetter, SquareContent, CLASSIC_FLEET,
+};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/*
+    Compares the array-based `BattleshipBoardContent` with the mask-based `BitBoard`.
+
+    Run with `cargo bench -p battleship_game_logic`.
+*/
+
+fn filled<T: ShipSetter>(mut board: T) -> T {
+    board.fill_with_seed(CLASSIC_FLEET, backtracking_placer, 42).unwrap();
+    board
+}
+
+// Note: Asks for every position of every ship, like the exhaustive search of the backtracking placer does
+fn check_all_positions(board: &dyn ShipSetter) -> usize {
+    let mut count = 0;
+    for ship in CLASSIC_FLEET {
+        for ix in (0..board.size()).map(|ix| BoardIndex::from_index(ix, board.side_length())) {
+            for direction in [Direction::Horizontal, Direction::Vertical] {
+                if let Ok(true) = board.can_place_ship(ix, *ship, direction) {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    count
+}
+
+fn can_place_ship(c: &mut Criterion) {
+    let mut group = c.benchmark_group("can_place_ship");
+    let array_board = filled(BattleshipBoardContent::new_initialized(SquareContent::Water));
+    let bitboard = filled(BitBoard::new_initialized(SquareContent::Water));
+    group.bench_function(BenchmarkId::new("array", "classic"), |b| {
+        b.iter(|| check_all_positions(black_box(&array_board)))
+    });
+    group.bench_function(BenchmarkId::new("bitboard", "classic"), |b| {
+        b.iter(|| check_all_positions(black_box(&bitboard)))
+    });
+    group.finish();
+}
+
+fn fill(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fill");
+    for (name, placer) in
+        [("random", random_placer as _), ("backtracking", backtracking_placer as _)]
+    {
+        group.bench_function(BenchmarkId::new("array", name), |b| {
+            b.iter(|| {
+                let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+                board.fill_with_seed(CLASSIC_FLEET, placer, black_box(42)).unwrap();
+                board
+            })
+        });
+        group.bench_function(BenchmarkId::new("bitboard", name), |b| {
+            b.iter(|| {
+                let mut board = BitBoard::new_initialized(SquareContent::Water);
+                board.fill_with_seed(CLASSIC_FLEET, placer, black_box(42)).unwrap();
+                board
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, can_place_ship, fill);
+criterion_main!(benches);
@@ -0,0 +1,553 @@
+use std::fmt;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    backtracking_placer, placement_rng, validate_layout, BattleshipBoardContent, BoardDimensions,
+    BoardFiller, BoardIndex, Direction, PlacementError, ShipFinder, ShipFindingResult,
+    SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Constraint propagation combined with backtracking
+    * Recursion with an early exit
+    * Using `Result` with a private error type for control flow
+
+    Recommended readings for this module:
+
+    * Battleship puzzles (Bimaru): https://en.wikipedia.org/wiki/Battleship_(puzzle)
+    * Constraint propagation: https://en.wikipedia.org/wiki/Local_consistency
+    * `SliceRandom`: https://docs.rs/rand/latest/rand/seq/trait.SliceRandom.html
+*/
+
+/// Fleet of the classic 10x10 Bimaru puzzle
+pub const BIMARU_FLEET: &[usize] = &[4, 3, 3, 2, 2, 2, 1, 1, 1, 1];
+
+/// Battleship solitaire puzzle (Bimaru).
+///
+/// The player knows the fleet, the number of ship squares in every row and column
+/// and a few revealed squares. Ships do not touch each other, not even diagonally.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BimaruPuzzle {
+    ship_lengths: Vec<usize>,
+    row_counts: Vec<usize>,
+    column_counts: Vec<usize>,
+    // Note: `Unknown` except for the revealed squares, which are `Water` or `Ship`
+    revealed: BattleshipBoardContent,
+}
+
+/// Result of solving a [`BimaruPuzzle`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BimaruSolution {
+    NoSolution,
+    Unique(BattleshipBoardContent),
+    /// Two of the solutions of the puzzle
+    Ambiguous(BattleshipBoardContent, BattleshipBoardContent),
+}
+
+// Note: Private unit struct, only used to leave the propagation as soon as a rule is violated
+struct Contradiction;
+
+impl BimaruPuzzle {
+    pub fn new(
+        ship_lengths: &[usize],
+        row_counts: &[usize],
+        column_counts: &[usize],
+    ) -> BimaruPuzzle {
+        if row_counts.len() != column_counts.len() {
+            panic!("Number of rows and columns differ");
+        }
+
+        BimaruPuzzle {
+            ship_lengths: ship_lengths.to_vec(),
+            row_counts: row_counts.to_vec(),
+            column_counts: column_counts.to_vec(),
+            revealed: BattleshipBoardContent::new_initialized_with_side_length(
+                SquareContent::Unknown,
+                row_counts.len(),
+            ),
+        }
+    }
+
+    /// Puzzle with the row and column counts of the given board and no revealed squares
+    pub fn from_solution(
+        solution: &BattleshipBoardContent,
+        ship_lengths: &[usize],
+    ) -> BimaruPuzzle {
+        let row_counts = solution
+            .rows()
+            .map(|r| r.as_slice().iter().filter(|c| c.is_ship()).count())
+            .collect::<Vec<_>>();
+        let column_counts = solution
+            .columns()
+            .map(|c| c.iter().filter(|c| c.is_ship()).count())
+            .collect::<Vec<_>>();
+        BimaruPuzzle::new(ship_lengths, &row_counts, &column_counts)
+    }
+
+    /// Creates a puzzle with exactly one solution.
+    ///
+    /// Squares of a random fleet are revealed until the solution is unique. Afterwards,
+    /// revealed squares that are not needed for a unique solution are hidden again.
+    pub fn generate(
+        side_length: usize,
+        ship_lengths: &[usize],
+        seed: u64,
+    ) -> Result<BimaruPuzzle, PlacementError> {
+        let mut rng = placement_rng(seed);
+        let mut solution = BattleshipBoardContent::new_initialized_with_side_length(
+            SquareContent::Water,
+            side_length,
+        );
+        solution.fill_with_rng(ship_lengths, backtracking_placer, &mut rng)?;
+
+        let mut puzzle = BimaruPuzzle::from_solution(&solution, ship_lengths);
+        loop {
+            match puzzle.solve() {
+                BimaruSolution::Unique(_) => break,
+                BimaruSolution::Ambiguous(a, b) => {
+                    // Note: `solution` differs from at least one of the two solutions at every
+                    //       square where they differ, so revealing such a square rules it out.
+                    let differing = (0..puzzle.size())
+                        .map(|ix| BoardIndex::from_index(ix, side_length))
+                        .filter(|ix| a[*ix] != b[*ix])
+                        .collect::<Vec<_>>();
+                    let ix = differing[rng.gen_range(0..differing.len())];
+                    puzzle.reveal(ix, solution[ix]);
+                }
+                BimaruSolution::NoSolution => unreachable!("The generated fleet is a solution"),
+            }
+        }
+
+        let mut revealed = puzzle.revealed_squares();
+        revealed.shuffle(&mut rng);
+        for ix in revealed {
+            puzzle.revealed[ix] = SquareContent::Unknown;
+            if !matches!(puzzle.solve(), BimaruSolution::Unique(_)) {
+                puzzle.revealed[ix] = solution[ix];
+            }
+        }
+
+        Ok(puzzle)
+    }
+
+    pub fn ship_lengths(&self) -> &[usize] {
+        &self.ship_lengths
+    }
+
+    pub fn row_counts(&self) -> &[usize] {
+        &self.row_counts
+    }
+
+    pub fn column_counts(&self) -> &[usize] {
+        &self.column_counts
+    }
+
+    /// Board with the revealed squares, all other squares are `Unknown`
+    pub fn revealed(&self) -> &BattleshipBoardContent {
+        &self.revealed
+    }
+
+    pub fn revealed_squares(&self) -> Vec<BoardIndex> {
+        (0..self.size())
+            .map(|ix| BoardIndex::from_index(ix, self.side_length()))
+            .filter(|ix| self.revealed[*ix] != SquareContent::Unknown)
+            .collect()
+    }
+
+    pub fn reveal(&mut self, ix: BoardIndex, content: SquareContent) {
+        if !matches!(content, SquareContent::Water | SquareContent::Ship) {
+            panic!("Only water and ship squares can be revealed");
+        }
+
+        self.revealed[ix] = content;
+    }
+
+    /// Finds up to two solutions, which is enough to tell whether the solution is unique
+    pub fn solve(&self) -> BimaruSolution {
+        let mut solutions = Vec::new();
+        self.search(self.revealed.clone(), &mut solutions);
+
+        let mut solutions = solutions.into_iter();
+        match (solutions.next(), solutions.next()) {
+            (None, _) => BimaruSolution::NoSolution,
+            (Some(solution), None) => BimaruSolution::Unique(solution),
+            (Some(a), Some(b)) => BimaruSolution::Ambiguous(a, b),
+        }
+    }
+
+    fn search(
+        &self,
+        mut board: BattleshipBoardContent,
+        solutions: &mut Vec<BattleshipBoardContent>,
+    ) {
+        if self.propagate(&mut board).is_err() {
+            return;
+        }
+
+        let unknown = board.iter().position(|c| c == SquareContent::Unknown);
+        match unknown {
+            None => {
+                // Note: Propagation has checked the counts, validation checks the fleet
+                if validate_layout(&board, &self.ship_lengths).is_ok() {
+                    solutions.push(board);
+                }
+            }
+            Some(ix) => {
+                for content in [SquareContent::Ship, SquareContent::Water] {
+                    if solutions.len() >= 2 {
+                        return;
+                    }
+
+                    let mut guess = board.clone();
+                    guess[ix] = content;
+                    self.search(guess, solutions);
+                }
+            }
+        }
+    }
+
+    /// Applies the rules until nothing changes anymore
+    fn propagate(&self, board: &mut BattleshipBoardContent) -> Result<(), Contradiction> {
+        let mut changed = true;
+        while changed {
+            changed = self.apply_counts(board)?;
+            changed |= apply_diagonals(board)?;
+            changed |= self.apply_fleet(board)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fills a row or column with water if all of its ships are known, or with
+    /// ships if all of its unknown squares are needed.
+    fn apply_counts(&self, board: &mut BattleshipBoardContent) -> Result<bool, Contradiction> {
+        fn fill(
+            contents: impl Iterator<Item = SquareContent>,
+            count: usize,
+        ) -> Result<Option<SquareContent>, Contradiction> {
+            let (ships, unknown) = contents.fold((0, 0), |(ships, unknown), c| match c {
+                SquareContent::Unknown => (ships, unknown + 1),
+                c if c.is_ship() => (ships + 1, unknown),
+                _ => (ships, unknown),
+            });
+
+            match (ships, ships + unknown) {
+                (s, t) if s > count || t < count => Err(Contradiction),
+                _ if unknown == 0 => Ok(None),
+                (s, _) if s == count => Ok(Some(SquareContent::Water)),
+                (_, t) if t == count => Ok(Some(SquareContent::Ship)),
+                _ => Ok(None),
+            }
+        }
+
+        let side_length = board.side_length();
+        let mut fills = Vec::new();
+        for row in board.rows() {
+            if let Some(content) =
+                fill(row.as_slice().iter().copied(), self.row_counts[row.row_index])?
+            {
+                let squares = (0..side_length).map(|c| BoardIndex::from_col_row(c, row.row_index));
+                fills.extend(
+                    squares
+                        .filter(|ix| board[*ix] == SquareContent::Unknown)
+                        .map(|ix| (ix, content)),
+                );
+            }
+        }
+
+        for column in board.columns() {
+            if let Some(content) = fill(column.iter(), self.column_counts[column.column_index])? {
+                fills.extend(
+                    column
+                        .squares()
+                        .filter(|ix| board[*ix] == SquareContent::Unknown)
+                        .map(|ix| (ix, content)),
+                );
+            }
+        }
+
+        // Note: A square that a row fills with ships and a column fills with water is a contradiction
+
+        let mut changed = false;
+        for (ix, content) in fills {
+            changed |= set(board, ix, content)?;
+        }
+
+        Ok(changed)
+    }
+
+    /// Checks the ships found so far against the fleet and closes ships that cannot grow any further
+    fn apply_fleet(&self, board: &mut BattleshipBoardContent) -> Result<bool, Contradiction> {
+        let side_length = board.side_length();
+        let ship_squares = (0..board.size())
+            .map(|ix| BoardIndex::from_index(ix, side_length))
+            .filter(|ix| board[*ix].is_ship())
+            .collect::<Vec<_>>();
+
+        // Note: Every ship is looked at from its first square only
+        let mut complete = Vec::new();
+        let mut partial = Vec::new();
+        for ix in ship_squares {
+            match board.try_find_ship(ix) {
+                ShipFindingResult::CompleteShip(ship) if ship.start() == ix => {
+                    complete.push(ship.length())
+                }
+                // Note: `ShipFinder` cannot tell the direction of a single square, so we check its neighbors ourselves
+                ShipFindingResult::PartialShip(ship) if ship.length() == 1 => {
+                    match ix.neighbors4(side_length).all(|n| board[n] == SquareContent::Water) {
+                        true => complete.push(1),
+                        false => partial.push((ix, ix)),
+                    }
+                }
+                ShipFindingResult::PartialShip(ship) if ship.start() == ix => {
+                    partial.push((ship.start(), ship.end()))
+                }
+                _ => {}
+            }
+        }
+
+        let mut remaining = self.ship_lengths.clone();
+        for length in complete {
+            match remaining.iter().position(|l| *l == length) {
+                Some(pos) => remaining.swap_remove(pos),
+                None => return Err(Contradiction),
+            };
+        }
+
+        let longest = remaining.iter().copied().max().unwrap_or(0);
+        let mut changed = false;
+        for (start, end) in partial {
+            let length = start.manhattan_distance(end) + 1;
+            if length > longest {
+                return Err(Contradiction);
+            }
+
+            if length == longest {
+                // Note: The ship cannot grow any further, so it ends here
+                let ends = match (start == end, start.row() == end.row()) {
+                    (true, _) => start.neighbors4(side_length).collect::<Vec<_>>(),
+                    (false, horizontal) => {
+                        let direction =
+                            if horizontal { Direction::Horizontal } else { Direction::Vertical };
+                        [start.try_previous(direction), end.try_next(direction, side_length)]
+                            .into_iter()
+                            .flatten()
+                            .collect()
+                    }
+                };
+                for ix in ends {
+                    changed |= set(board, ix, SquareContent::Water)?;
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+/// Squares diagonally next to a ship are water because ships must not touch each other
+fn apply_diagonals(board: &mut BattleshipBoardContent) -> Result<bool, Contradiction> {
+    let side_length = board.side_length();
+    let mut changed = false;
+    for ix in (0..board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+        if !board[ix].is_ship() {
+            continue;
+        }
+
+        for (c, r) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+            if let Some(diagonal) = ix.try_offset(c, r, side_length) {
+                changed |= set(board, diagonal, SquareContent::Water)?;
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Sets an unknown square. Returns whether the board changed.
+fn set(
+    board: &mut BattleshipBoardContent,
+    ix: BoardIndex,
+    content: SquareContent,
+) -> Result<bool, Contradiction> {
+    match board[ix] {
+        SquareContent::Unknown => {
+            board[ix] = content;
+            Ok(true)
+        }
+        current if current == content => Ok(false),
+        _ => Err(Contradiction),
+    }
+}
+
+impl BoardDimensions for BimaruPuzzle {
+    fn side_length(&self) -> usize {
+        self.revealed.side_length()
+    }
+}
+
+impl fmt::Display for BimaruPuzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.revealed.rows() {
+            for content in row.as_slice() {
+                match content {
+                    SquareContent::Unknown => write!(f, " .")?,
+                    content => write!(f, " {}", char::from(*content))?,
+                }
+            }
+
+            writeln!(f, " |{:>2}", self.row_counts[row.row_index])?;
+        }
+
+        writeln!(f, "{}", "--".repeat(self.side_length()))?;
+        for count in self.column_counts.iter() {
+            write!(f, "{:>2}", count)?;
+        }
+
+        writeln!(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::ShipSetter;
+
+    use super::*;
+
+    // Note: Ships are A1-D1 (4), F1 (1), A3-A4 (2) and C3-C5 (3) on a 6x6 board
+    fn get_solution() -> BattleshipBoardContent {
+        let mut board =
+            BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 6);
+        board.try_place_ship("A1".into(), 4, Direction::Horizontal).unwrap();
+        board.try_place_ship("F1".into(), 1, Direction::Horizontal).unwrap();
+        board.try_place_ship("A3".into(), 2, Direction::Vertical).unwrap();
+        board.try_place_ship("C3".into(), 3, Direction::Vertical).unwrap();
+        board
+    }
+
+    #[test]
+    fn counts_from_solution() {
+        let puzzle = BimaruPuzzle::from_solution(&get_solution(), &[4, 3, 2, 1]);
+        assert_eq!(&[5, 0, 2, 2, 1, 0], puzzle.row_counts());
+        assert_eq!(&[3, 1, 4, 1, 0, 1], puzzle.column_counts());
+        assert!(puzzle.revealed_squares().is_empty());
+    }
+
+    #[test]
+    fn solve_by_propagation() {
+        // Note: The first row contains four of the five squares of the 4-ship and the 1-ship.
+        //       Counts and fleet leave only one way to place them.
+        let puzzle = BimaruPuzzle::from_solution(&get_solution(), &[4, 3, 2, 1]);
+        assert_eq!(BimaruSolution::Unique(get_solution()), puzzle.solve());
+
+        let mut board = puzzle.revealed().clone();
+        assert!(puzzle.propagate(&mut board).is_ok());
+        assert_eq!(SquareContent::Water, board[BoardIndex::from("E1")]);
+    }
+
+    #[test]
+    fn ambiguous_without_reveals() {
+        // Note: The ships can be at A1 and C3 or at C1 and A3
+        let puzzle = BimaruPuzzle::new(&[1, 1], &[1, 0, 1, 0, 0], &[1, 0, 1, 0, 0]);
+        let BimaruSolution::Ambiguous(a, b) = puzzle.solve() else {
+            panic!("Expected two solutions")
+        };
+        assert_ne!(a, b);
+
+        let mut puzzle = puzzle;
+        puzzle.reveal("A1".into(), SquareContent::Water);
+        let BimaruSolution::Unique(solution) = puzzle.solve() else {
+            panic!("Expected a unique solution")
+        };
+        assert_eq!(SquareContent::Ship, solution[BoardIndex::from("C1")]);
+        assert_eq!(SquareContent::Ship, solution[BoardIndex::from("A3")]);
+    }
+
+    #[rstest]
+    #[case(&[2, 0, 0, 0, 0], &[1, 1, 0, 0, 0], &[1, 1])] // ships would touch
+    #[case(&[2, 0, 0, 0, 0], &[1, 1, 0, 0, 0], &[3])] // ship does not fit the counts
+    #[case(&[1, 0, 0, 0, 0], &[1, 0, 0, 0, 0], &[1, 1])] // too few ship squares
+    fn no_solution(
+        #[case] row_counts: &[usize],
+        #[case] column_counts: &[usize],
+        #[case] ship_lengths: &[usize],
+    ) {
+        assert_eq!(
+            BimaruSolution::NoSolution,
+            BimaruPuzzle::new(ship_lengths, row_counts, column_counts).solve()
+        );
+    }
+
+    #[test]
+    fn revealed_squares_are_kept() {
+        let mut puzzle = BimaruPuzzle::from_solution(&get_solution(), &[4, 3, 2, 1]);
+        puzzle.reveal("A1".into(), SquareContent::Water);
+        assert_eq!(BimaruSolution::NoSolution, puzzle.solve());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only water and ship squares can be revealed")]
+    fn reveal_hit_ship() {
+        BimaruPuzzle::new(&[1], &[1, 0, 0, 0, 0], &[1, 0, 0, 0, 0])
+            .reveal("A1".into(), SquareContent::HitShip);
+    }
+
+    #[rstest]
+    #[case(6, &[3, 2, 2, 1, 1, 1], 1)]
+    #[case(6, &[3, 2, 2, 1, 1, 1], 2)]
+    #[case(8, &[4, 3, 2, 2, 1, 1, 1], 3)]
+    #[case(10, BIMARU_FLEET, 4)]
+    fn generate_unique(
+        #[case] side_length: usize,
+        #[case] ship_lengths: &[usize],
+        #[case] seed: u64,
+    ) {
+        let puzzle = BimaruPuzzle::generate(side_length, ship_lengths, seed).unwrap();
+        let BimaruSolution::Unique(solution) = puzzle.solve() else {
+            panic!("Expected a unique solution")
+        };
+
+        assert_eq!(ship_lengths.len(), validate_layout(&solution, ship_lengths).unwrap().len());
+        assert_eq!(
+            puzzle.row_counts(),
+            BimaruPuzzle::from_solution(&solution, ship_lengths).row_counts()
+        );
+        assert!(puzzle.revealed_squares().iter().all(|ix| puzzle.revealed()[*ix] == solution[*ix]));
+
+        // Note: Every revealed square is needed
+        for ix in puzzle.revealed_squares() {
+            let mut easier = puzzle.clone();
+            easier.revealed[ix] = SquareContent::Unknown;
+            assert!(matches!(easier.solve(), BimaruSolution::Ambiguous(_, _)));
+        }
+    }
+
+    #[test]
+    fn generate_is_reproducible() {
+        assert_eq!(
+            BimaruPuzzle::generate(8, &[3, 2, 2, 1, 1], 42),
+            BimaruPuzzle::generate(8, &[3, 2, 2, 1, 1], 42)
+        );
+    }
+
+    #[test]
+    fn generate_fails_for_too_long_ships() {
+        assert!(BimaruPuzzle::generate(5, &[6], 42).is_err());
+    }
+
+    #[test]
+    fn display() {
+        let mut puzzle = BimaruPuzzle::new(&[1, 1], &[1, 0, 1, 0, 0], &[1, 0, 1, 0, 0]);
+        puzzle.reveal("C1".into(), SquareContent::Ship);
+        puzzle.reveal("A1".into(), SquareContent::Water);
+        let text = puzzle.to_string();
+        assert!(text.starts_with(" ~ . S . . | 1\n . . . . . | 0\n"));
+        assert!(text.ends_with("----------\n 1 0 1 0 0\n"));
+    }
+}
@@ -0,0 +1,264 @@
+use std::{convert::TryFrom, ops::Index};
+
+use crate::{
+    BattleshipBoardContent, BoardDimensions, BoardIndex, InvalidBoardSize, SquareAccessor,
+    SquareContent, BOARD_SIDE_LENGTH, MIN_BOARD_SIDE_LENGTH,
+};
+
+/*
+    Learnings in this module:
+
+    * Storing a board as bit masks (bitboards)
+    * Overriding default methods of a trait for a faster implementation
+    * Returning references to `static` values
+    * Benchmarking with criterion (see `benches/board_backends.rs`)
+
+    Recommended readings for this module:
+
+    * Bitboards: https://www.chessprogramming.org/Bitboards
+    * Integer methods like `count_ones`: https://doc.rust-lang.org/std/primitive.u128.html
+    * `static` items: https://doc.rust-lang.org/reference/items/static-items.html
+    * Criterion: https://bheisler.github.io/criterion.rs/book/
+*/
+
+/// Largest side length of a [`BitBoard`] (every square needs a bit in a `u128`)
+pub const MAX_BITBOARD_SIDE_LENGTH: usize = 11;
+
+// Note: Same order as the variants of `SquareContent`, so `content as usize` is the index of its mask
+static SQUARE_CONTENTS: [SquareContent; 5] = [
+    SquareContent::Water,
+    SquareContent::Ship,
+    SquareContent::HitShip,
+    SquareContent::SunkenShip,
+    SquareContent::Unknown,
+];
+
+/// Battleship board that stores one bit mask per kind of square content.
+///
+/// Checking whether a ship can be placed needs a few mask operations instead of one
+/// call per square. Use [`BitBoard::set`] to change squares.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    side_length: usize,
+    // Note: Bit `row * side_length + column` is set in exactly one of the masks
+    masks: [u128; 5],
+}
+
+// Note: There is no `IndexMut` implementation. A bitboard does not store `SquareContent`
+//       values, so there is nothing we could hand out a mutable reference to. This is also
+//       why `BitBoard` implements `SquareAccessor` itself instead of getting it from the
+//       blanket implementation for `IndexMut` boards.
+impl BitBoard {
+    pub fn new() -> BitBoard {
+        BitBoard::new_initialized(SquareContent::default())
+    }
+
+    pub fn new_initialized(initial_content: SquareContent) -> BitBoard {
+        BitBoard::new_initialized_with_side_length(initial_content, BOARD_SIDE_LENGTH)
+    }
+
+    pub fn new_with_side_length(side_length: usize) -> BitBoard {
+        BitBoard::new_initialized_with_side_length(SquareContent::default(), side_length)
+    }
+
+    pub fn new_initialized_with_side_length(
+        initial_content: SquareContent,
+        side_length: usize,
+    ) -> BitBoard {
+        if !(MIN_BOARD_SIDE_LENGTH..=MAX_BITBOARD_SIDE_LENGTH).contains(&side_length) {
+            panic!("Invalid board side length");
+        }
+
+        let mut masks = [0; 5];
+        masks[initial_content as usize] =
+            (0..side_length * side_length).fold(0, |mask, ix| mask | 1 << ix);
+        BitBoard { side_length, masks }
+    }
+
+    fn bit(&self, ix: BoardIndex) -> u128 {
+        1 << ix.to_index(self.side_length)
+    }
+
+    pub fn set(&mut self, ix: BoardIndex, content: SquareContent) {
+        let bit = self.bit(ix);
+        self.masks.iter_mut().for_each(|m| *m &= !bit);
+        self.masks[content as usize] |= bit;
+    }
+
+    /// Bit mask of all squares with the given content (bit `row * side_length + column`)
+    pub fn mask(&self, content: SquareContent) -> u128 {
+        self.masks[content as usize]
+    }
+
+    /// Number of squares with the given content
+    pub fn count(&self, content: SquareContent) -> usize {
+        self.mask(content).count_ones() as usize
+    }
+
+    /// Bit mask of the rectangle from `first` (top left) to `last` (bottom right)
+    fn area(&self, first: BoardIndex, last: BoardIndex) -> u128 {
+        let width = last.column() - first.column() + 1;
+        let row = ((1u128 << width) - 1) << first.column();
+        (first.row()..=last.row()).fold(0, |mask, r| mask | row << (r * self.side_length))
+    }
+}
+
+impl Default for BitBoard {
+    fn default() -> Self {
+        BitBoard::new()
+    }
+}
+
+impl BoardDimensions for BitBoard {
+    fn side_length(&self) -> usize {
+        self.side_length
+    }
+}
+
+impl Index<BoardIndex> for BitBoard {
+    type Output = SquareContent;
+
+    fn index(&self, ix: BoardIndex) -> &Self::Output {
+        let bit = self.bit(ix);
+        let content = self.masks.iter().position(|m| m & bit != 0).unwrap();
+        &SQUARE_CONTENTS[content]
+    }
+}
+
+impl SquareAccessor for BitBoard {
+    fn is_free(&self, ix: BoardIndex) -> bool {
+        self.mask(SquareContent::Water) & self.bit(ix) != 0
+    }
+
+    fn set_ship(&mut self, ix: BoardIndex) {
+        self.set(ix, SquareContent::Ship);
+    }
+
+    fn clear(&mut self, ix: BoardIndex) {
+        self.set(ix, SquareContent::Water);
+    }
+
+    fn is_area_free(&self, first: BoardIndex, last: BoardIndex) -> bool {
+        let area = self.area(first, last);
+        self.mask(SquareContent::Water) & area == area
+    }
+}
+
+impl TryFrom<&BattleshipBoardContent> for BitBoard {
+    type Error = InvalidBoardSize;
+
+    fn try_from(board: &BattleshipBoardContent) -> Result<Self, Self::Error> {
+        if board.side_length() > MAX_BITBOARD_SIDE_LENGTH {
+            return Err(InvalidBoardSize);
+        }
+
+        let mut result = BitBoard::new_with_side_length(board.side_length());
+        for ix in (0..board.size()).map(|ix| BoardIndex::from_index(ix, board.side_length())) {
+            result.set(ix, board[ix]);
+        }
+
+        Ok(result)
+    }
+}
+
+impl From<&BitBoard> for BattleshipBoardContent {
+    fn from(board: &BitBoard) -> Self {
+        let mut result = BattleshipBoardContent::new_with_side_length(board.side_length());
+        for ix in (0..board.size()).map(|ix| BoardIndex::from_index(ix, board.side_length())) {
+            result[ix] = board[ix];
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{
+        backtracking_placer, AdjacencyRule, BoardFiller, Direction, RuleAwareSetter, ShipFinder,
+        ShipFindingResult, ShipSetter, CLASSIC_FLEET,
+    };
+
+    use super::*;
+
+    #[test]
+    fn new_and_set() {
+        let mut board = BitBoard::new_initialized_with_side_length(SquareContent::Water, 3);
+        assert_eq!(9, board.count(SquareContent::Water));
+        assert_eq!(SquareContent::Water, board[BoardIndex::from("C3")]);
+
+        board.set("C3".into(), SquareContent::HitShip);
+        assert_eq!(SquareContent::HitShip, board[BoardIndex::from("C3")]);
+        assert_eq!(1 << 8, board.mask(SquareContent::HitShip));
+        assert_eq!(8, board.count(SquareContent::Water));
+        assert_eq!(SquareContent::Unknown, BitBoard::new()[BoardIndex::from("J10")]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid board side length")]
+    fn too_large() {
+        BitBoard::new_with_side_length(12);
+    }
+
+    #[rstest]
+    #[case("A1", 3, Direction::Horizontal)]
+    #[case("B2", 4, Direction::Vertical)]
+    #[case("H10", 3, Direction::Horizontal)]
+    #[case("J1", 1, Direction::Vertical)]
+    fn placement_like_array_board(
+        #[case] ix: &str,
+        #[case] ship_length: usize,
+        #[case] direction: Direction,
+    ) {
+        let mut array_board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        array_board.try_place_ship("C4".into(), 3, Direction::Horizontal).unwrap();
+        let mut bitboard = BitBoard::try_from(&array_board).unwrap();
+
+        assert_eq!(
+            array_board.can_place_ship(ix.into(), ship_length, direction),
+            bitboard.can_place_ship(ix.into(), ship_length, direction)
+        );
+        assert_eq!(
+            array_board.try_place_ship(ix.into(), ship_length, direction),
+            bitboard.try_place_ship(ix.into(), ship_length, direction)
+        );
+        assert_eq!(array_board, BattleshipBoardContent::from(&bitboard));
+    }
+
+    #[test]
+    fn adjacency() {
+        let mut board = BitBoard::new_initialized(SquareContent::Water);
+        board.try_place_ship("B2".into(), 3, Direction::Horizontal).unwrap();
+        assert!(!board.can_place_ship("E3".into(), 2, Direction::Vertical).unwrap());
+        assert!(board.can_place_ship("F3".into(), 2, Direction::Vertical).unwrap());
+
+        let setter = RuleAwareSetter::new(&mut board, AdjacencyRule::MayTouch);
+        assert!(setter.can_place_ship("E3".into(), 2, Direction::Vertical).unwrap());
+        assert!(!setter.can_place_ship("D1".into(), 2, Direction::Vertical).unwrap());
+    }
+
+    #[test]
+    fn fill_and_find() {
+        let mut array_board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        array_board.fill_with_seed(CLASSIC_FLEET, backtracking_placer, 42).unwrap();
+        let mut bitboard = BitBoard::new_initialized(SquareContent::Water);
+        bitboard.fill_with_seed(CLASSIC_FLEET, backtracking_placer, 42).unwrap();
+
+        assert_eq!(array_board, BattleshipBoardContent::from(&bitboard));
+        assert_eq!(17, bitboard.count(SquareContent::Ship));
+
+        let ix = (0..100)
+            .map(|ix| BoardIndex::from_index(ix, 10))
+            .find(|ix| bitboard[*ix] == SquareContent::Ship)
+            .unwrap();
+        assert!(matches!(bitboard.try_find_ship(ix), ShipFindingResult::CompleteShip(_)));
+    }
+
+    #[test]
+    fn conversion_fails_for_large_boards() {
+        let board = BattleshipBoardContent::new_with_side_length(12);
+        assert_eq!(Err(InvalidBoardSize), BitBoard::try_from(&board));
+    }
+}
@@ -1,58 +1,224 @@
-use crate::{BoardIndex, Direction, ShipSetter};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{BoardIndex, Direction, ErrorKind, PlacementError, PlacementStrategy, ShipSetter};
 
 /*
     Learnings in this module:
 
     * Function pointer types
     * Working with random numbers
+    * Backtracking with recursive functions
+    * Reproducible random numbers with seedable generators
     * Mocking of external traits
 
     Recommended readings for this module:
 
     * Function pointer types: https://doc.rust-lang.org/reference/types/function-pointer.html
     * rand crate: https://crates.io/crates/rand
+    * Portability of random numbers: https://rust-random.github.io/book/portability.html
     * Mockall - mocking external traits: https://docs.rs/mockall/0.10.1/mockall/#external-traits
     * Mockall - call counting: https://docs.rs/mockall/0.10.1/mockall/#callcounts
 */
 
-pub type ShipPlacer = fn(ship: usize, &mut dyn ShipSetter);
+/// Places all ships of a fleet on the board
+pub type ShipPlacer = fn(ship_lengths: &[usize], &mut dyn ShipSetter, rng: &mut dyn RngCore) -> Result<(), PlacementError>;
+
+/// Creates the random number generator used for placing ships.
+///
+/// The generator produces the same numbers for a given seed on every
+/// platform (including wasm) and with every version of this crate.
+pub fn placement_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+fn try_place_randomly(
+    ship: usize,
+    setter: &mut dyn ShipSetter,
+    rng: &mut dyn RngCore,
+) -> Result<Option<(BoardIndex, Direction)>, PlacementError> {
+    let side_length = setter.side_length();
+    if ship > side_length {
+        return Err(PlacementError::new(ErrorKind::ShipTooLong));
+    }
 
-pub fn random_placer(ship: usize, setter: &mut dyn ShipSetter) {
+    // Note: We sample `u32` instead of `usize`. The size of `usize` differs
+    //       between platforms (e.g. wasm32 vs. x86_64), which would lead to
+    //       different boards for the same seed.
+    let side = side_length as u32;
+    let positions = (side_length - ship + 1) as u32;
     for _ in 0..1000 {
         let direction: Direction;
-        let col: usize;
-        let row: usize;
-        match rand::random::<usize>() % 2 == 0 {
+        let col: u32;
+        let row: u32;
+        match rng.gen::<bool>() {
             true => {
                 direction = Direction::Horizontal;
-                col = rand::random::<usize>() % (10 - ship);
-                row = rand::random::<usize>() % 10;
+                col = rng.gen_range(0..positions);
+                row = rng.gen_range(0..side);
             }
             false => {
-    
-            direction = Direction::Vertical;
-                col = rand::random::<usize>() % 10;
-                row = rand::random::<usize>() % (10 - ship);
+                direction = Direction::Vertical;
+                col = rng.gen_range(0..side);
+                row = rng.gen_range(0..positions);
             }
         };
 
-        if setter.try_place_ship(BoardIndex::from_col_row(col, row), ship, direction).unwrap() {
-            return;
+        let ix = BoardIndex::from_col_row(col as usize, row as usize);
+        if setter.try_place_ship(ix, ship, direction)? {
+            return Ok(Some((ix, direction)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Places every ship at a random position.
+///
+/// Gives up with [`ErrorKind::BoardTooOccupied`] if a ship cannot be placed after 1000 attempts.
+/// Ships that have already been placed stay on the board in this case.
+pub fn random_placer(ship_lengths: &[usize], setter: &mut dyn ShipSetter, rng: &mut dyn RngCore) -> Result<(), PlacementError> {
+    for ship in ship_lengths.iter().copied() {
+        if try_place_randomly(ship, setter, rng)?.is_none() {
+            return Err(PlacementError::new(ErrorKind::BoardTooOccupied));
         }
     }
 
-    panic!("Cannot position ships, board is too occupied.");    
+    Ok(())
+}
+
+/// Places ships at random positions and falls back to a complete search if the
+/// fleet does not fit that way.
+///
+/// Returns [`ErrorKind::BoardTooOccupied`] only if there is no valid layout at all.
+/// In this case, the board is left unchanged.
+pub fn backtracking_placer(ship_lengths: &[usize], setter: &mut dyn ShipSetter, rng: &mut dyn RngCore) -> Result<(), PlacementError> {
+    // Note: Most fleets fit at the first attempt. Trying random positions first
+    //       is fast and leads to the same layouts as `random_placer` for a given seed.
+    let mut placed = Vec::new();
+    for ship in ship_lengths.iter().copied() {
+        match try_place_randomly(ship, setter, rng)? {
+            Some((ix, direction)) => placed.push((ix, ship, direction)),
+            None => {
+                for (ix, ship, direction) in placed {
+                    setter.remove_ship(ix, ship, direction)?;
+                }
+                return place_exhaustively(ship_lengths, setter, rng);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn place_exhaustively(ship_lengths: &[usize], setter: &mut dyn ShipSetter, rng: &mut dyn RngCore) -> Result<(), PlacementError> {
+    let side_length = setter.side_length();
+
+    // Note: Long ships have fewer options, so placing them first leads to dead ends earlier
+    let mut ships = ship_lengths.to_vec();
+    ships.sort_unstable_by(|a, b| b.cmp(a));
+
+    // Note: Ships with the same length share a shuffled list of candidates. This allows
+    //       `backtrack` to skip layouts that only differ in the order of equal ships.
+    let mut candidates: Vec<Vec<(BoardIndex, Direction)>> = Vec::with_capacity(ships.len());
+    for (i, ship) in ships.iter().copied().enumerate() {
+        if i > 0 && ships[i - 1] == ship {
+            candidates.push(candidates[i - 1].clone());
+            continue;
+        }
+
+        if ship > side_length {
+            return Err(PlacementError::new(ErrorKind::ShipTooLong));
+        }
+
+        let mut positions = Vec::new();
+        for direction in [Direction::Horizontal, Direction::Vertical] {
+            for ix in (0..side_length * side_length).map(|ix| BoardIndex::from_index(ix, side_length)) {
+                if setter.can_place_ship(ix, ship, direction).is_ok() {
+                    positions.push((ix, direction));
+                }
+            }
+        }
+
+        shuffle(&mut positions, rng);
+        candidates.push(positions);
+    }
+
+    match backtrack(setter, &ships, &candidates, 0)? {
+        true => Ok(()),
+        false => Err(PlacementError::new(ErrorKind::BoardTooOccupied)),
+    }
+}
+
+fn backtrack(
+    setter: &mut dyn ShipSetter,
+    ships: &[usize],
+    candidates: &[Vec<(BoardIndex, Direction)>],
+    first_candidate: usize,
+) -> Result<bool, PlacementError> {
+    let (ship, remaining) = match ships.split_first() {
+        Some((ship, remaining)) => (*ship, remaining),
+        None => return Ok(true),
+    };
+
+    // Note: Placing a ship never creates room for other ships. If there are fewer
+    //       free squares than squares needed for the remaining ships, we can stop here.
+    let side_length = setter.side_length();
+    let free = (0..side_length * side_length)
+        .map(|ix| BoardIndex::from_index(ix, side_length))
+        .filter(|ix| matches!(setter.can_place_ship(*ix, 1, Direction::Horizontal), Ok(true)))
+        .count();
+    if free < ships.iter().sum() {
+        return Ok(false);
+    }
+
+    for (i, (ix, direction)) in candidates[0].iter().copied().enumerate().skip(first_candidate) {
+        if !setter.try_place_ship(ix, ship, direction)? {
+            continue;
+        }
+
+        let next_first_candidate = if remaining.first() == Some(&ship) { i + 1 } else { 0 };
+        if backtrack(setter, remaining, &candidates[1..], next_first_candidate)? {
+            return Ok(true);
+        }
+
+        setter.remove_ship(ix, ship, direction)?;
+    }
+
+    Ok(false)
+}
+
+// Note: `SliceRandom::shuffle` samples `usize` values. We use `u32` to get the
+//       same order on every platform (see `try_place_randomly`).
+fn shuffle<T>(items: &mut [T], rng: &mut dyn RngCore) {
+    for i in (1..items.len()).rev() {
+        let j = rng.gen_range(0..=i as u32) as usize;
+        items.swap(i, j);
+    }
 }
 
 pub trait BoardFiller {
-    fn fill(&mut self, ship_lengths: &[usize], placer: ShipPlacer);
+    fn fill(&mut self, ship_lengths: &[usize], placer: ShipPlacer) -> Result<(), PlacementError>;
+    fn fill_with_rng(&mut self, ship_lengths: &[usize], placer: ShipPlacer, rng: &mut dyn RngCore) -> Result<(), PlacementError>;
+    fn fill_with_seed(&mut self, ship_lengths: &[usize], placer: ShipPlacer, seed: u64) -> Result<(), PlacementError>;
+    fn fill_with_strategy(&mut self, ship_lengths: &[usize], strategy: &mut dyn PlacementStrategy) -> Result<(), PlacementError>;
 }
 
 impl<T> BoardFiller for T where T: ShipSetter {
-    fn fill(&mut self, ship_lengths: &[usize], placer: ShipPlacer) {
-        for ship_length in ship_lengths {
-            placer(*ship_length, self);
-        }
+    fn fill(&mut self, ship_lengths: &[usize], placer: ShipPlacer) -> Result<(), PlacementError> {
+        self.fill_with_rng(ship_lengths, placer, &mut rand::thread_rng())
+    }
+
+    fn fill_with_rng(&mut self, ship_lengths: &[usize], placer: ShipPlacer, rng: &mut dyn RngCore) -> Result<(), PlacementError> {
+        placer(ship_lengths, self, rng)
+    }
+
+    fn fill_with_seed(&mut self, ship_lengths: &[usize], placer: ShipPlacer, seed: u64) -> Result<(), PlacementError> {
+        self.fill_with_rng(ship_lengths, placer, &mut placement_rng(seed))
+    }
+
+    fn fill_with_strategy(&mut self, ship_lengths: &[usize], strategy: &mut dyn PlacementStrategy) -> Result<(), PlacementError> {
+        strategy.place(ship_lengths, self)
     }
 }
 
@@ -60,10 +226,14 @@ impl<T> BoardFiller for T where T: ShipSetter {
 mod tests {
     use super::*;
     use mockall::mock;
-    use crate::PlacementError;
+    use rstest::rstest;
+    use crate::{AdjacencyRule, BattleshipBoardContent, BoardDimensions, RuleAwareSetter, ShipShape, SquareContent};
 
     mock! {
         MyFillableBoard {}
+        impl BoardDimensions for MyFillableBoard {
+            fn side_length(&self) -> usize;
+        }
         impl ShipSetter for MyFillableBoard {
             fn can_place_ship(
                 &self,
@@ -76,21 +246,90 @@ mod tests {
                 ix: BoardIndex,
                 ship_length: usize,
                 direction: Direction,
-            ) -> Result<bool, PlacementError>;        }
+            ) -> Result<bool, PlacementError>;
+            fn remove_ship(
+                &mut self,
+                ix: BoardIndex,
+                ship_length: usize,
+                direction: Direction,
+            ) -> Result<(), PlacementError>;
+            fn can_place_shape<'a>(&self, origin: BoardIndex, shape: &'a ShipShape) -> Result<bool, PlacementError>;
+            fn try_place_shape<'a>(&mut self, origin: BoardIndex, shape: &'a ShipShape) -> Result<bool, PlacementError>;
+            fn remove_shape<'a>(&mut self, origin: BoardIndex, shape: &'a ShipShape) -> Result<(), PlacementError>;
+        }
     }
 
     #[test]
     fn fill() {
         let mut mock = MockMyFillableBoard::new();
+        mock.expect_side_length().return_const(10usize);
         mock.expect_try_place_ship().times(3).return_const(Ok(true));
-        mock.fill(&[2, 3, 4], random_placer);
+        assert_eq!(Ok(()), mock.fill(&[2, 3, 4], random_placer));
+    }
+
+    #[test]
+    fn fill_with_side_length() {
+        for side_length in [8, 15] {
+            let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, side_length);
+            board.fill(&[5, 4, 3, 3, 2], random_placer).unwrap();
+            assert_eq!(17, board.iter().filter(|s| *s == SquareContent::Ship).count());
+        }
+    }
+
+    #[rstest]
+    #[case(random_placer)]
+    #[case(backtracking_placer)]
+    fn fill_ship_too_long(#[case] placer: ShipPlacer) {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 4);
+        assert_eq!(Err(PlacementError::new(ErrorKind::ShipTooLong)), board.fill(&[5], placer));
     }
 
     #[test]
-    #[should_panic]
     fn fill_failure() {
         let mut mock = MockMyFillableBoard::new();
-        mock.expect_can_place_ship().return_const(Ok(false));
-        mock.fill(&[2], random_placer);
+        mock.expect_side_length().return_const(10usize);
+        mock.expect_try_place_ship().return_const(Ok(false));
+        assert_eq!(Err(PlacementError::new(ErrorKind::BoardTooOccupied)), mock.fill(&[2], random_placer));
+    }
+
+    #[test]
+    fn backtracking_fills_dense_board() {
+        // Note: The ships cover the entire board. Random placement regularly
+        //       runs into dead ends for this fleet.
+        for seed in 0..50 {
+            let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 3);
+            let mut setter = RuleAwareSetter::new(&mut board, AdjacencyRule::MayTouch);
+            setter.fill_with_seed(&[3, 2, 2, 1, 1], backtracking_placer, seed).unwrap();
+            assert!(board.iter().all(|s| s == SquareContent::Ship));
+        }
+    }
+
+    #[test]
+    fn backtracking_reports_impossible_fleet() {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 4);
+        assert_eq!(
+            Err(PlacementError::new(ErrorKind::BoardTooOccupied)),
+            board.fill_with_seed(&[3, 3, 3], backtracking_placer, 42)
+        );
+        assert!(board.iter().all(|s| s == SquareContent::Water));
+    }
+
+    #[test]
+    fn backtracking_keeps_random_layouts() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        let mut other = board.clone();
+        board.fill_with_seed(&[5, 4, 3, 3, 2], random_placer, 42).unwrap();
+        other.fill_with_seed(&[5, 4, 3, 3, 2], backtracking_placer, 42).unwrap();
+        assert_eq!(board, other);
+    }
+
+    #[test]
+    fn fill_with_seed() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        let mut other = board.clone();
+        board.fill_with_seed(&[5, 4, 3, 3, 2], random_placer, 42).unwrap();
+        other.fill_with_rng(&[5, 4, 3, 3, 2], random_placer, &mut placement_rng(42)).unwrap();
+        assert_eq!(board, other);
+        assert_eq!(17, board.iter().filter(|s| *s == SquareContent::Ship).count());
     }
 }
@@ -1,15 +1,16 @@
 use std::{
     fmt::Display,
-    ops::{Add, Index, IndexMut, Sub},
+    ops::{Index, IndexMut},
     str::FromStr,
 };
 
-use crate::{GenericBoardContent, BOARD_SIDE_LENGTH};
+use crate::{GenericBoardContent, MAX_BOARD_SIDE_LENGTH};
 
 /*
     Learnings in this module:
 
-    * Building a new type based on a basic data type
+    * Building a struct with private fields
+    * Traits with default method implementations
     * Writing constructor functions
     * Writing functions for converting types (into, from)
     * String handling fundamentals
@@ -19,7 +20,8 @@ use crate::{GenericBoardContent, BOARD_SIDE_LENGTH};
 
     Recommended readings for this module:
 
-    * newtype pattern: https://doc.rust-lang.org/rust-by-example/generics/new_types.html
+    * Default implementations: https://doc.rust-lang.org/book/ch10-02-traits.html#default-implementations
+    * Slice patterns: https://doc.rust-lang.org/reference/patterns.html#slice-patterns
     * Strings: https://doc.rust-lang.org/rust-by-example/std/str.html
     * Operator overloading: https://doc.rust-lang.org/std/ops/index.html
     * rstest crate: https://docs.rs/rstest/0.10.0/rstest/index.html
@@ -28,96 +30,153 @@ use crate::{GenericBoardContent, BOARD_SIDE_LENGTH};
     * `parse` method: https://doc.rust-lang.org/std/primitive.str.html#method.parse
 */
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Ord, PartialOrd)]
-pub struct BoardIndex(usize);
+// Note: Field order matters for the derived `Ord` implementation. Comparing
+//       the row first results in the same order as the squares have on the board.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoardIndex {
+    row: usize,
+    column: usize,
+}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     Horizontal,
     Vertical,
 }
 
+/// Gives access to the size of a square board.
+pub trait BoardDimensions {
+    fn side_length(&self) -> usize;
+
+    fn size(&self) -> usize {
+        self.side_length() * self.side_length()
+    }
+
+    fn contains(&self, ix: BoardIndex) -> bool {
+        ix.column() < self.side_length() && ix.row() < self.side_length()
+    }
+}
+
 impl BoardIndex {
     pub fn new() -> BoardIndex {
-        BoardIndex(0)
+        BoardIndex { row: 0, column: 0 }
     }
 
-    pub fn from_index(index: usize) -> BoardIndex {
-        if index >= BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH {
+    pub fn from_index(index: usize, side_length: usize) -> BoardIndex {
+        if index >= side_length * side_length {
             panic!("Index out of bounds");
         }
 
-        BoardIndex(index)
+        BoardIndex::from_col_row(index % side_length, index / side_length)
     }
 
     pub fn from_col_row(col: usize, row: usize) -> BoardIndex {
-        if col >= BOARD_SIDE_LENGTH {
+        if col >= MAX_BOARD_SIDE_LENGTH {
             panic!("Column out of bounds");
         }
 
-        if row >= BOARD_SIDE_LENGTH {
+        if row >= MAX_BOARD_SIDE_LENGTH {
             panic!("Row out of bounds");
         }
 
-        BoardIndex(row * BOARD_SIDE_LENGTH + col)
+        BoardIndex { row, column: col }
+    }
+
+    pub fn to_index(&self, side_length: usize) -> usize {
+        if self.column >= side_length || self.row >= side_length {
+            panic!("Index out of bounds");
+        }
+
+        self.row * side_length + self.column
     }
 
     pub fn column(&self) -> usize {
-        self.0 % BOARD_SIDE_LENGTH
+        self.column
     }
 
     pub fn row(&self) -> usize {
-        self.0 / BOARD_SIDE_LENGTH
+        self.row
     }
 
-    pub fn try_next(&self, direction: Direction) -> Option<BoardIndex> {
+    pub fn try_next(&self, direction: Direction, side_length: usize) -> Option<BoardIndex> {
         match direction {
-            Direction::Horizontal if self.column() < 9 => Some(BoardIndex::from_index(self.0 + 1)),
-            Direction::Vertical if self.row() < 9 => Some(BoardIndex::from_index(self.0 + 10)),
+            Direction::Horizontal if self.column + 1 < side_length => Some(BoardIndex::from_col_row(self.column + 1, self.row)),
+            Direction::Vertical if self.row + 1 < side_length => Some(BoardIndex::from_col_row(self.column, self.row + 1)),
             _ => None,
         }
     }
 
     pub fn try_previous(&self, direction: Direction) -> Option<BoardIndex> {
         match direction {
-            Direction::Horizontal if self.column() > 0 => Some(BoardIndex::from_index(self.0 - 1)),
-            Direction::Vertical if self.row() > 0 => Some(BoardIndex::from_index(self.0 - 10)),
+            Direction::Horizontal if self.column > 0 => Some(BoardIndex::from_col_row(self.column - 1, self.row)),
+            Direction::Vertical if self.row > 0 => Some(BoardIndex::from_col_row(self.column, self.row - 1)),
             _ => None,
         }
     }
 
-    pub fn next_column(&self) -> BoardIndex {
-        if self.column() >= 9 {
+    /// Square `column_delta` columns and `row_delta` rows away, or `None` if it is not on the board
+    pub fn try_offset(&self, column_delta: isize, row_delta: isize, side_length: usize) -> Option<BoardIndex> {
+        let column = self.column.checked_add_signed(column_delta)?;
+        let row = self.row.checked_add_signed(row_delta)?;
+        match column < side_length && row < side_length {
+            true => Some(BoardIndex::from_col_row(column, row)),
+            false => None,
+        }
+    }
+
+    /// Squares above, left, right and below (in board order) that are on the board
+    pub fn neighbors4(&self, side_length: usize) -> impl Iterator<Item = BoardIndex> {
+        let ix = *self; // Note: Copy, so the iterator does not borrow `self`
+        [(0, -1), (-1, 0), (1, 0), (0, 1)].into_iter().filter_map(move |(c, r)| ix.try_offset(c, r, side_length))
+    }
+
+    /// All squares around (including diagonals, in board order) that are on the board
+    pub fn neighbors8(&self, side_length: usize) -> impl Iterator<Item = BoardIndex> {
+        let ix = *self;
+        (-1..=1)
+            .flat_map(|r| (-1..=1).map(move |c| (c, r)))
+            .filter(|offset| *offset != (0, 0))
+            .filter_map(move |(c, r)| ix.try_offset(c, r, side_length))
+    }
+
+    /// Number of steps between the squares if you can only move horizontally and vertically
+    pub fn manhattan_distance(&self, other: BoardIndex) -> usize {
+        self.column.abs_diff(other.column) + self.row.abs_diff(other.row)
+    }
+
+    /// Number of steps between the squares if you can also move diagonally (like a king in chess)
+    pub fn chebyshev_distance(&self, other: BoardIndex) -> usize {
+        self.column.abs_diff(other.column).max(self.row.abs_diff(other.row))
+    }
+
+    pub fn next_column(&self, side_length: usize) -> BoardIndex {
+        if self.column + 1 >= side_length {
             panic!("Already at last column");
         }
-        BoardIndex::from_index(self.0 + 1)
+        BoardIndex::from_col_row(self.column + 1, self.row)
     }
 
-    pub fn next_row(&self) -> BoardIndex {
-        if self.row() >= 9 {
+    pub fn next_row(&self, side_length: usize) -> BoardIndex {
+        if self.row + 1 >= side_length {
             panic!("Already at last row");
         }
-        BoardIndex::from_index(self.0 + 10)
+        BoardIndex::from_col_row(self.column, self.row + 1)
     }
 
     pub fn previous_column(&self) -> BoardIndex {
-        if self.column() == 0 {
+        if self.column == 0 {
             panic!("Already at first column");
         }
-        BoardIndex::from_index(self.0 - 1)
+        BoardIndex::from_col_row(self.column - 1, self.row)
     }
 
     pub fn previous_row(&self) -> BoardIndex {
-        if self.row() == 0 {
+        if self.row == 0 {
             panic!("Already at first row");
         }
-        BoardIndex::from_index(self.0 - 10)
-    }
-}
-
-impl From<BoardIndex> for usize {
-    fn from(ix: BoardIndex) -> Self {
-        ix.0
+        BoardIndex::from_col_row(self.column, self.row - 1)
     }
 }
 
@@ -127,12 +186,6 @@ impl From<BoardIndex> for String {
     }
 }
 
-impl From<usize> for BoardIndex {
-    fn from(ix: usize) -> Self {
-        BoardIndex::from_index(ix)
-    }
-}
-
 impl From<&str> for BoardIndex {
     fn from(ix: &str) -> Self {
         BoardIndex::from_str(ix).unwrap()
@@ -149,34 +202,31 @@ impl FromStr for BoardIndex {
 
         let location = location.as_bytes(); // Note shadowing
 
-        // Check if length of location is ok (A1..J10).
+        // Check if length of location is ok (A1..Z26).
         // Discuss difference between location.chars().count() and location.len()
         if !matches!(location.len(), 2..=3) {
             return Err("Invalid length");
         }
 
-        // Parse column letter (A..J, a..j)
+        // Parse column letter (A..Z, a..z). Note that the board the location
+        // is used for might be smaller. Boards check their bounds when indexed.
         let col = match location[0] {
-            r if matches!(r, b'A'..=b'J') => (r - b'A') as usize, // Check experimental `if let` syntax
-            r if matches!(r, b'a'..=b'j') => (r - b'a') as usize,
+            r if r.is_ascii_uppercase() => (r - b'A') as usize, // Check experimental `if let` syntax
+            r if r.is_ascii_lowercase() => (r - b'a') as usize,
             _ => return Err("Invalid column"),
         };
 
-        // Parse the row letter(s) (1..10)
+        // Parse the row number (1..26)
         // Note: No mut here
-        let row = if location.len() == 3 {
-            if location[1..] != [b'1', b'0'] {
-                // Note slice pattern
-                return Err("Invalid row");
-            }
-
-            9
-        } else {
-            match location[1] {
-                c if matches!(c, b'1'..=b'9') => (c - b'1') as usize,
-                _ => return Err("Invalid row"),
-            }
+        let row = match location[1..] {
+            [r @ b'1'..=b'9'] => (r - b'1') as usize,
+            // Note slice pattern with bindings
+            [t @ b'1'..=b'9', u @ b'0'..=b'9'] => ((t - b'0') as usize) * 10 + (u - b'0') as usize - 1,
+            _ => return Err("Invalid row"),
         };
+        if row >= MAX_BOARD_SIDE_LENGTH {
+            return Err("Invalid row");
+        }
 
         Ok(BoardIndex::from_col_row(col, row))
     }
@@ -184,28 +234,7 @@ impl FromStr for BoardIndex {
 
 impl Display for BoardIndex {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}",
-            char::from_u32((b'A' as usize + self.0 / BOARD_SIDE_LENGTH) as u32).unwrap(),
-            self.0 % BOARD_SIDE_LENGTH + 1
-        )
-    }
-}
-
-impl Add<usize> for BoardIndex {
-    type Output = Self;
-
-    fn add(self, other: usize) -> Self {
-        (self.0 + other).into()
-    }
-}
-
-impl Sub<usize> for BoardIndex {
-    type Output = Self;
-
-    fn sub(self, other: usize) -> Self {
-        (self.0 - other).into()
+        write!(f, "{}{}", char::from_u32((b'A' as usize + self.column) as u32).unwrap(), self.row + 1)
     }
 }
 
@@ -213,19 +242,20 @@ impl<T> Index<BoardIndex> for GenericBoardContent<T> {
     type Output = T;
 
     fn index(&self, ix: BoardIndex) -> &Self::Output {
-        &self[Into::<usize>::into(ix)]
+        &self[ix.to_index(self.side_length())]
     }
 }
 
 impl<T> IndexMut<BoardIndex> for GenericBoardContent<T> {
     fn index_mut(&mut self, ix: BoardIndex) -> &mut Self::Output {
-        &mut self[Into::<usize>::into(ix)]
+        let side_length = self.side_length();
+        &mut self[ix.to_index(side_length)]
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{BattleshipBoardContent, SquareContent};
+    use crate::{BattleshipBoardContent, SquareContent, BOARD_SIDE_LENGTH};
 
     use super::*;
     use rstest::rstest;
@@ -233,60 +263,72 @@ mod tests {
     #[test]
     fn new() {
         let ix = BoardIndex::new();
-        assert_eq!(0usize, ix.into());
+        assert_eq!(0, ix.to_index(BOARD_SIDE_LENGTH));
     }
 
-    #[test]
-    fn from_index() {
-        let ix = BoardIndex::from_index(10);
-        assert_eq!(BOARD_SIDE_LENGTH, ix.into());
+    #[rstest]
+    #[case(10, 10, 0, 1)]
+    #[case(10, 8, 2, 1)]
+    #[case(63, 8, 7, 7)]
+    #[case(224, 15, 14, 14)]
+    fn from_index(#[case] index: usize, #[case] side_length: usize, #[case] col: usize, #[case] row: usize) {
+        let ix = BoardIndex::from_index(index, side_length);
+        assert_eq!(BoardIndex::from_col_row(col, row), ix);
+        assert_eq!(index, ix.to_index(side_length));
     }
 
     #[test]
-    fn from_usize() {
-        assert_eq!(BoardIndex::from_index(10), 10.into());
+    #[should_panic]
+    fn from_index_out_of_bounds() {
+        BoardIndex::from_index(BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH, BOARD_SIDE_LENGTH);
     }
 
     #[test]
     #[should_panic]
-    fn from_index_out_of_bounds() {
-        BoardIndex::from_index(BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH);
+    fn to_index_out_of_bounds() {
+        BoardIndex::from_col_row(8, 0).to_index(8);
     }
 
     #[test]
     fn from_row_col() {
         let ix = BoardIndex::from_col_row(0, 1);
-        assert_eq!(BOARD_SIDE_LENGTH, ix.into());
+        assert_eq!(BOARD_SIDE_LENGTH, ix.to_index(BOARD_SIDE_LENGTH));
     }
 
     #[test]
     #[should_panic]
     fn from_row_col_row_out_of_bounds() {
-        BoardIndex::from_col_row(0, BOARD_SIDE_LENGTH);
+        BoardIndex::from_col_row(0, MAX_BOARD_SIDE_LENGTH);
     }
 
     #[test]
     #[should_panic]
     fn from_row_col_col_out_of_bounds() {
-        BoardIndex::from_col_row(BOARD_SIDE_LENGTH, 0);
+        BoardIndex::from_col_row(MAX_BOARD_SIDE_LENGTH, 0);
     }
 
     #[test]
     fn from_str_lowest() {
         let ix = BoardIndex::from_str("A1").unwrap();
-        assert_eq!(0usize, ix.into());
+        assert_eq!(0, ix.to_index(BOARD_SIDE_LENGTH));
     }
 
     #[test]
     fn from_str_highest() {
         let ix = BoardIndex::from_str("J10").unwrap();
-        assert_eq!(BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH - 1, ix.into());
+        assert_eq!(BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH - 1, ix.to_index(BOARD_SIDE_LENGTH));
+    }
+
+    #[test]
+    fn from_str_largest_board() {
+        let ix = BoardIndex::from_str("Z26").unwrap();
+        assert_eq!(BoardIndex::from_col_row(MAX_BOARD_SIDE_LENGTH - 1, MAX_BOARD_SIDE_LENGTH - 1), ix);
     }
 
     #[test]
     fn from_str_lowercase() {
         let ix = BoardIndex::from_str("a1").unwrap();
-        assert_eq!(0usize, ix.into());
+        assert_eq!(0, ix.to_index(BOARD_SIDE_LENGTH));
     }
 
     #[test]
@@ -307,13 +349,14 @@ mod tests {
 
     #[rstest]
     #[case("B01")] // leading zero
-    #[case("B11")] // too high column
+    #[case("B27")] // too high row
     #[case("B0")] // too low column
     #[case("B")] // missing column
     #[case("9")] // missing row
     #[case("J1A")] // invalid length
     #[case("AA10")] // invalid length
     #[case("99")] // missing row
+    #[case("[1")] // invalid column
     fn try_parse_errors(#[case] location: &'static str) {
         assert!(BoardIndex::from_str(location).is_err());
     }
@@ -338,57 +381,79 @@ mod tests {
     #[case(0, 0, "A1")]
     #[case(9, 9, "J10")]
     #[case(1, 1, "B2")]
+    #[case(2, 0, "C1")]
+    #[case(0, 14, "A15")]
     fn try_display(#[case] col: usize, #[case] row: usize, #[case] location: &'static str) {
         assert_eq!(location, format!("{}", BoardIndex::from_col_row(col, row)));
     }
 
+    #[rstest]
+    #[case("A1")]
+    #[case("J10")]
+    #[case("C7")]
+    #[case("O15")]
+    fn display_roundtrip(#[case] location: &'static str) {
+        assert_eq!(location, BoardIndex::from_str(location).unwrap().to_string());
+    }
+
     #[test]
-    fn add() {
-        assert_eq!(BoardIndex::from_index(2), BoardIndex::from_index(1) + 1);
+    fn order_follows_board_layout() {
+        assert!(BoardIndex::from_col_row(9, 0) < BoardIndex::from_col_row(0, 1));
     }
 
     #[test]
-    fn sub() {
-        assert_eq!(BoardIndex::from_index(0), BoardIndex::from_index(1) - 1);
+    fn index_board() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        let ix = BoardIndex::new();
+        board[ix] = SquareContent::HitShip; // index mut
+        assert_eq!(SquareContent::HitShip, board[ix]); // index
     }
 
     #[test]
-    #[should_panic]
-    fn add_overflow() {
-        let _ = BoardIndex::from_index(BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH - 1) + 1;
+    fn index_board_with_side_length() {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 15);
+        let ix = BoardIndex::from_str("O15").unwrap();
+        board[ix] = SquareContent::HitShip;
+        assert_eq!(SquareContent::HitShip, board[ix]);
+        assert_eq!(SquareContent::HitShip, board[board.size() - 1]);
     }
 
     #[test]
     #[should_panic]
-    fn sub_overflow() {
-        let _ = BoardIndex::from_index(0) - 1;
+    fn index_board_out_of_bounds() {
+        let board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 8);
+        let _ = board[BoardIndex::from_str("I1").unwrap()];
     }
 
-    #[test]
-    fn index_board() {
-        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
-        let ix = BoardIndex::new();
-        board[ix] = SquareContent::HitShip; // index mut
-        assert_eq!(SquareContent::HitShip, board[ix]); // index
+    #[rstest]
+    #[case(8, "H8", true)]
+    #[case(8, "I8", false)]
+    #[case(8, "H9", false)]
+    fn contains(#[case] side_length: usize, #[case] location: &'static str, #[case] expected: bool) {
+        let board = BattleshipBoardContent::new_with_side_length(side_length);
+        assert_eq!(expected, board.contains(location.into()));
     }
 
     #[rstest]
-    #[case(BoardIndex::from_index(0), Direction::Horizontal, Some(BoardIndex::from_index(1)))]
-    #[case(BoardIndex::from_index(0), Direction::Vertical, Some(BoardIndex::from_index(10)))]
-    #[case(BoardIndex::from_index(9), Direction::Horizontal, None)]
-    #[case(BoardIndex::from_col_row(0, 9), Direction::Vertical, None)]
+    #[case(BoardIndex::from_col_row(0, 0), Direction::Horizontal, 10, Some(BoardIndex::from_col_row(1, 0)))]
+    #[case(BoardIndex::from_col_row(0, 0), Direction::Vertical, 10, Some(BoardIndex::from_col_row(0, 1)))]
+    #[case(BoardIndex::from_col_row(9, 0), Direction::Horizontal, 10, None)]
+    #[case(BoardIndex::from_col_row(0, 9), Direction::Vertical, 10, None)]
+    #[case(BoardIndex::from_col_row(9, 0), Direction::Horizontal, 15, Some(BoardIndex::from_col_row(10, 0)))]
+    #[case(BoardIndex::from_col_row(0, 7), Direction::Vertical, 8, None)]
     fn try_get_next(
         #[case] ix: BoardIndex,
         #[case] direction: Direction,
+        #[case] side_length: usize,
         #[case] expected_ix: Option<BoardIndex>,
     ) {
-        assert_eq!(expected_ix, ix.try_next(direction));
+        assert_eq!(expected_ix, ix.try_next(direction, side_length));
     }
 
     #[rstest]
-    #[case(BoardIndex::from_index(1), Direction::Horizontal, Some(BoardIndex::from_index(0)))]
-    #[case(BoardIndex::from_col_row(0, 1), Direction::Vertical, Some(BoardIndex::from_index(0)))]
-    #[case(BoardIndex::from_index(0), Direction::Horizontal, None)]
+    #[case(BoardIndex::from_col_row(1, 0), Direction::Horizontal, Some(BoardIndex::new()))]
+    #[case(BoardIndex::from_col_row(0, 1), Direction::Vertical, Some(BoardIndex::new()))]
+    #[case(BoardIndex::new(), Direction::Horizontal, None)]
     #[case(BoardIndex::from_col_row(9, 0), Direction::Vertical, None)]
     fn try_get_prev(
         #[case] ix: BoardIndex,
@@ -398,10 +463,60 @@ mod tests {
         assert_eq!(expected_ix, ix.try_previous(direction));
     }
 
+    #[rstest]
+    #[case("B2", -1, -1, 10, Some("A1"))]
+    #[case("B2", 2, 3, 10, Some("D5"))]
+    #[case("A1", -1, 0, 10, None)]
+    #[case("J10", 0, 1, 10, None)]
+    #[case("J10", 0, 1, 11, Some("J11"))]
+    fn try_offset(
+        #[case] ix: &str,
+        #[case] column_delta: isize,
+        #[case] row_delta: isize,
+        #[case] side_length: usize,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            expected.map(BoardIndex::from),
+            BoardIndex::from(ix).try_offset(column_delta, row_delta, side_length)
+        );
+    }
+
+    #[rstest]
+    #[case("B2", &["B1", "A2", "C2", "B3"])]
+    #[case("A1", &["B1", "A2"])]
+    #[case("H8", &["H7", "G8"])]
+    fn neighbors4(#[case] ix: &str, #[case] expected: &[&str]) {
+        let expected = expected.iter().map(|s| BoardIndex::from(*s)).collect::<Vec<_>>();
+        assert_eq!(expected, BoardIndex::from(ix).neighbors4(8).collect::<Vec<_>>());
+    }
+
+    #[rstest]
+    #[case("B2", &["A1", "B1", "C1", "A2", "C2", "A3", "B3", "C3"])]
+    #[case("A1", &["B1", "A2", "B2"])]
+    #[case("H1", &["G1", "G2", "H2"])]
+    fn neighbors8(#[case] ix: &str, #[case] expected: &[&str]) {
+        let expected = expected.iter().map(|s| BoardIndex::from(*s)).collect::<Vec<_>>();
+        assert_eq!(expected, BoardIndex::from(ix).neighbors8(8).collect::<Vec<_>>());
+    }
+
+    #[rstest]
+    #[case("A1", "A1", 0, 0)]
+    #[case("A1", "C2", 3, 2)]
+    #[case("J1", "A10", 18, 9)]
+    #[case("D5", "E4", 2, 1)]
+    fn distances(#[case] a: &str, #[case] b: &str, #[case] manhattan: usize, #[case] chebyshev: usize) {
+        let (a, b) = (BoardIndex::from(a), BoardIndex::from(b));
+        assert_eq!(manhattan, a.manhattan_distance(b));
+        assert_eq!(manhattan, b.manhattan_distance(a));
+        assert_eq!(chebyshev, a.chebyshev_distance(b));
+        assert_eq!(chebyshev, b.chebyshev_distance(a));
+    }
+
     #[test]
     #[should_panic]
     fn next_row_invalid() {
-        BoardIndex::from_str("A10").unwrap().next_row();
+        BoardIndex::from_str("A10").unwrap().next_row(BOARD_SIDE_LENGTH);
     }
 
     #[test]
@@ -413,7 +528,7 @@ mod tests {
     #[test]
     #[should_panic]
     fn next_column_invalid() {
-        BoardIndex::from_str("J1").unwrap().next_column();
+        BoardIndex::from_str("J1").unwrap().next_column(BOARD_SIDE_LENGTH);
     }
 
     #[test]
@@ -424,7 +539,7 @@ mod tests {
 
     #[test]
     fn next_row() {
-        assert_eq!(9, BoardIndex::from_str("A9").unwrap().next_row().row());
+        assert_eq!(9, BoardIndex::from_str("A9").unwrap().next_row(BOARD_SIDE_LENGTH).row());
     }
 
     #[test]
@@ -434,11 +549,16 @@ mod tests {
 
     #[test]
     fn next_column() {
-        assert_eq!(9, BoardIndex::from_str("I9").unwrap().next_column().column());
+        assert_eq!(9, BoardIndex::from_str("I9").unwrap().next_column(BOARD_SIDE_LENGTH).column());
     }
 
     #[test]
     fn previous_column() {
         assert_eq!(8, BoardIndex::from_str("J9").unwrap().previous_column().column());
     }
+
+    #[test]
+    fn next_row_larger_board() {
+        assert_eq!(10, BoardIndex::from_str("A10").unwrap().next_row(15).row());
+    }
 }
@@ -26,11 +26,19 @@ impl BoardIndexRangeInclusive {
         BoardIndexRangeInclusive(start..=end)
     }
 
+    pub fn start(&self) -> BoardIndex {
+        *self.0.start()
+    }
+
+    pub fn end(&self) -> BoardIndex {
+        *self.0.end()
+    }
+
     pub fn length(&self) -> usize {
         if self.0.start().column() == self.0.end().column() {
-           (self.0.start().row() as i8 - self.0.end().row() as i8).abs() as usize + 1
+           self.0.start().row().abs_diff(self.0.end().row()) + 1
         } else {
-           (self.0.start().column() as i8 - self.0.end().column() as i8).abs() as usize + 1
+           self.0.start().column().abs_diff(self.0.end().column()) + 1
         }
     }
 }
@@ -68,12 +76,15 @@ impl Iterator for BoardIndexIterator {
     fn next(&mut self) -> Option<Self::Item> {
         match self.current {
             None => { self.current = Some(self.start); self.current },
-            Some(c) => {
-                match c.try_next(self.direction) {
-                    Some(x) => if x <= self.end { self.current = Some(x); self.current } else { None },
-                    None => None,
-                }
-            }
+            Some(c) if c < self.end => {
+                // Note: The range never leaves the board because `end` is on the board
+                self.current = Some(match self.direction {
+                    Direction::Horizontal => BoardIndex::from_col_row(c.column() + 1, c.row()),
+                    Direction::Vertical => BoardIndex::from_col_row(c.column(), c.row() + 1),
+                });
+                self.current
+            },
+            Some(_) => None,
         }
     }
 }
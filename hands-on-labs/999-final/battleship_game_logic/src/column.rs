@@ -0,0 +1,127 @@
+use std::ops::Index;
+
+use crate::{BoardDimensions, BoardIndex, GenericBoardContent};
+
+/*
+    Learnings in this module:
+
+    * Views that cannot be represented as slices
+    * Returning iterators that borrow from a struct
+
+    Recommended readings for this module:
+
+    * Strided access with `step_by`: https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.step_by
+    * Iterators: https://doc.rust-lang.org/rust-by-example/trait/iter.html
+*/
+
+pub struct Column<'a, T> {
+    board: &'a GenericBoardContent<T>,
+    pub column_index: usize,
+}
+
+impl<'a, T: Default + Copy> Column<'a, T> {
+    pub fn new(board: &'a GenericBoardContent<T>, column: usize) -> Self {
+        Column { board, column_index: column }
+    }
+
+    // Note: In contrast to `Row`, there is no `as_slice`. The squares of a column
+    //       are not next to each other in memory, so we offer an iterator instead.
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        self.board.as_slice().iter().skip(self.column_index).step_by(self.board.side_length()).copied()
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = BoardIndex> {
+        let column = self.column_index;
+        (0..self.board.side_length()).map(move |row| BoardIndex::from_col_row(column, row))
+    }
+
+    pub fn len(&self) -> usize {
+        self.board.side_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.column_index < (self.board.side_length() - 1)
+    }
+}
+
+impl<'a, T> Index<usize> for Column<'a, T> {
+    type Output = T;
+
+    fn index(&self, ix: usize) -> &Self::Output {
+        let side_length = self.board.side_length();
+        if ix >= side_length {
+            panic!("Index out of bounds");
+        }
+
+        &self.board[ix * side_length + self.column_index]
+    }
+}
+
+pub struct ColumnsIterator<'a, T> {
+    board: &'a GenericBoardContent<T>,
+    next_column: usize,
+}
+
+impl<'a, T> ColumnsIterator<'a, T> {
+    pub fn new(board: &'a GenericBoardContent<T>) -> Self {
+        ColumnsIterator { board, next_column: 0 }
+    }
+}
+
+impl<'a, T> Iterator for ColumnsIterator<'a, T> {
+    type Item = Column<'a, T>;
+
+    fn next(&mut self) -> Option<Column<'a, T>> {
+        if self.next_column >= self.board.side_length() {
+            return None;
+        }
+
+        let current = self.next_column;
+        self.next_column += 1;
+        Some(Column { board: self.board, column_index: current })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BattleshipBoardContent, BoardIndex, SquareContent, BOARD_SIDE_LENGTH};
+
+    #[test]
+    fn column_values() {
+        let mut b = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        b[BoardIndex::from("B3")] = SquareContent::Ship;
+        b[BoardIndex::from("C3")] = SquareContent::HitShip;
+
+        let column = b.column(1);
+        assert_eq!(BOARD_SIDE_LENGTH, column.len());
+        assert_eq!(SquareContent::Ship, column[2]);
+        assert_eq!(
+            vec![BoardIndex::from("B3")],
+            column.squares().filter(|ix| b[*ix] == SquareContent::Ship).collect::<Vec<_>>()
+        );
+        assert_eq!(1, column.iter().filter(|c| *c == SquareContent::Ship).count());
+        assert_eq!(BOARD_SIDE_LENGTH, column.iter().count());
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    fn column_out_of_bounds() {
+        BattleshipBoardContent::new_with_side_length(8).column(8);
+    }
+
+    #[test]
+    fn column_iterator_with_side_length() {
+        let mut b = BattleshipBoardContent::new_with_side_length(8);
+        b[BoardIndex::from("H2")] = SquareContent::Ship;
+        assert_eq!(8, b.columns().count());
+        let last = b.columns().last().unwrap();
+        assert_eq!(8, last.len());
+        assert!(!last.has_next());
+        assert_eq!(SquareContent::Ship, last[1]);
+        assert_eq!(Some(1), last.iter().position(|c| c == SquareContent::Ship));
+    }
+}
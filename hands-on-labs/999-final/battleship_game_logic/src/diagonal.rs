@@ -0,0 +1,189 @@
+use std::ops::Index;
+
+use crate::{BoardDimensions, BoardIndex, GenericBoardContent};
+
+/*
+    Learnings in this module:
+
+    * Views whose length depends on their position
+    * Deriving an iterator from a start value with `successors`
+
+    Recommended readings for this module:
+
+    * `std::iter::successors`: https://doc.rust-lang.org/std/iter/fn.successors.html
+    * Iterators: https://doc.rust-lang.org/rust-by-example/trait/iter.html
+*/
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiagonalDirection {
+    /// From top left to bottom right
+    DownRight,
+    /// From top right to bottom left
+    DownLeft,
+}
+
+impl DiagonalDirection {
+    fn column_delta(&self) -> isize {
+        match self {
+            DiagonalDirection::DownRight => 1,
+            DiagonalDirection::DownLeft => -1,
+        }
+    }
+}
+
+pub struct Diagonal<'a, T> {
+    board: &'a GenericBoardContent<T>,
+    // Note: First square of the diagonal, always in the first row or in the first/last column
+    start: BoardIndex,
+    pub direction: DiagonalDirection,
+}
+
+impl<'a, T: Default + Copy> Diagonal<'a, T> {
+    /// Diagonal in the given direction that runs through `ix`
+    pub fn new(board: &'a GenericBoardContent<T>, ix: BoardIndex, direction: DiagonalDirection) -> Self {
+        let steps = match direction {
+            DiagonalDirection::DownRight => ix.row().min(ix.column()),
+            DiagonalDirection::DownLeft => ix.row().min(board.side_length() - 1 - ix.column()),
+        };
+        let start = match direction {
+            DiagonalDirection::DownRight => BoardIndex::from_col_row(ix.column() - steps, ix.row() - steps),
+            DiagonalDirection::DownLeft => BoardIndex::from_col_row(ix.column() + steps, ix.row() - steps),
+        };
+        Diagonal { board, start, direction }
+    }
+
+    pub fn start(&self) -> BoardIndex {
+        self.start
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = BoardIndex> {
+        let (side_length, column_delta) = (self.board.side_length(), self.direction.column_delta());
+        std::iter::successors(Some(self.start), move |ix| ix.try_offset(column_delta, 1, side_length))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let board = self.board;
+        self.squares().map(move |ix| board[ix])
+    }
+
+    pub fn len(&self) -> usize {
+        let side_length = self.board.side_length();
+        let columns_left = match self.direction {
+            DiagonalDirection::DownRight => side_length - self.start.column(),
+            DiagonalDirection::DownLeft => self.start.column() + 1,
+        };
+        columns_left.min(side_length - self.start.row())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, T: Default + Copy> Index<usize> for Diagonal<'a, T> {
+    type Output = T;
+
+    fn index(&self, ix: usize) -> &Self::Output {
+        match self.squares().nth(ix) {
+            Some(square) => &self.board[square],
+            None => panic!("Index out of bounds"),
+        }
+    }
+}
+
+/// Iterates over all diagonals of a board, starting with the ones that begin in the first row
+pub struct DiagonalsIterator<'a, T> {
+    board: &'a GenericBoardContent<T>,
+    direction: DiagonalDirection,
+    next_diagonal: usize,
+}
+
+impl<'a, T> DiagonalsIterator<'a, T> {
+    pub fn new(board: &'a GenericBoardContent<T>, direction: DiagonalDirection) -> Self {
+        DiagonalsIterator { board, direction, next_diagonal: 0 }
+    }
+}
+
+impl<'a, T: Default + Copy> Iterator for DiagonalsIterator<'a, T> {
+    type Item = Diagonal<'a, T>;
+
+    fn next(&mut self) -> Option<Diagonal<'a, T>> {
+        let side_length = self.board.side_length();
+        if self.next_diagonal >= 2 * side_length - 1 {
+            return None;
+        }
+
+        // Note: The first `side_length` diagonals start in the first row, the others
+        //       in the first (down right) or last (down left) column.
+        let current = self.next_diagonal;
+        self.next_diagonal += 1;
+        let start = match (current < side_length, self.direction) {
+            (true, _) => BoardIndex::from_col_row(current, 0),
+            (false, DiagonalDirection::DownRight) => BoardIndex::from_col_row(0, current - side_length + 1),
+            (false, DiagonalDirection::DownLeft) => BoardIndex::from_col_row(side_length - 1, current - side_length + 1),
+        };
+        Some(Diagonal { board: self.board, start, direction: self.direction })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{BattleshipBoardContent, SquareContent};
+
+    use super::*;
+
+    fn squares(squares: &[&str]) -> Vec<BoardIndex> {
+        squares.iter().map(|s| BoardIndex::from(*s)).collect()
+    }
+
+    #[rstest]
+    #[case("C2", DiagonalDirection::DownRight, &["B1", "C2", "D3", "E4"])]
+    #[case("A1", DiagonalDirection::DownRight, &["A1", "B2", "C3", "D4", "E5"])]
+    #[case("E1", DiagonalDirection::DownRight, &["E1"])]
+    #[case("A5", DiagonalDirection::DownRight, &["A5"])]
+    #[case("C2", DiagonalDirection::DownLeft, &["D1", "C2", "B3", "A4"])]
+    #[case("E5", DiagonalDirection::DownLeft, &["E5"])]
+    #[case("A5", DiagonalDirection::DownLeft, &["E1", "D2", "C3", "B4", "A5"])]
+    fn diagonal_through(#[case] ix: &str, #[case] direction: DiagonalDirection, #[case] expected: &[&str]) {
+        let board = BattleshipBoardContent::new_with_side_length(5);
+        let diagonal = board.diagonal(ix.into(), direction);
+        assert_eq!(squares(expected), diagonal.squares().collect::<Vec<_>>());
+        assert_eq!(expected.len(), diagonal.len());
+    }
+
+    #[test]
+    fn diagonal_values() {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 5);
+        board[BoardIndex::from("C3")] = SquareContent::Ship;
+
+        let diagonal = board.diagonal("A5".into(), DiagonalDirection::DownLeft);
+        assert_eq!(SquareContent::Ship, diagonal[2]);
+        assert_eq!(Some(2), diagonal.iter().position(|c| c == SquareContent::Ship));
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    fn diagonal_out_of_bounds() {
+        let board = BattleshipBoardContent::new_with_side_length(5);
+        let _ = board.diagonal("B1".into(), DiagonalDirection::DownRight)[4];
+    }
+
+    #[rstest]
+    #[case(DiagonalDirection::DownRight)]
+    #[case(DiagonalDirection::DownLeft)]
+    fn all_diagonals(#[case] direction: DiagonalDirection) {
+        let board = BattleshipBoardContent::new_with_side_length(6);
+        assert_eq!(11, board.diagonals(direction).count());
+
+        // Note: Every square is on exactly one diagonal
+        let mut all = board.diagonals(direction).flat_map(|d| d.squares().collect::<Vec<_>>()).collect::<Vec<_>>();
+        assert_eq!(36, all.len());
+        all.sort();
+        all.dedup();
+        assert_eq!(36, all.len());
+        assert!(board.diagonals(direction).all(|d| d.len() == d.squares().count()));
+    }
+}
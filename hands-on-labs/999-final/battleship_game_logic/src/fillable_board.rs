@@ -1,6 +1,6 @@
 use std::ops::IndexMut;
 
-use crate::{BOARD_SIDE_LENGTH, BoardIndex, Direction, SquareContent};
+use crate::{BoardDimensions, BoardIndex, Direction, ShipShape, SquareContent};
 
 /*
     Learnings in this module:
@@ -9,27 +9,41 @@ use crate::{BOARD_SIDE_LENGTH, BoardIndex, Direction, SquareContent};
     * Custom error type
     * Local functions
     * Mock objects for unit testing
+    * Generic functions with `?Sized` bounds
 
     Recommended readings for this module:
 
     * mockall crate: https://github.com/asomers/mockall
     * Defining error types: https://doc.rust-lang.org/rust-by-example/error/multiple_error_types/define_error_type.html
     * Functions: https://doc.rust-lang.org/book/ch03-03-how-functions-work.html
+    * Dynamically sized types: https://doc.rust-lang.org/reference/dynamically-sized-types.html
 */
 
-#[cfg_attr(test, mockall::automock)]
-pub trait SquareAccessor {
+pub trait SquareAccessor: BoardDimensions {
     fn is_free(&self, ix: BoardIndex) -> bool;
     fn set_ship(&mut self, ix: BoardIndex);
+    fn clear(&mut self, ix: BoardIndex);
+
+    /// Checks whether all squares in the rectangle from `first` (top left) to `last` (bottom right) are free.
+    ///
+    /// Boards that can check many squares at once (e.g. [`crate::BitBoard`]) should override this.
+    fn is_area_free(&self, first: BoardIndex, last: BoardIndex) -> bool {
+        // Discuss: ranges with 1..100 and 1..=100
+        (first.row()..=last.row())
+            .all(|r| (first.column()..=last.column()).all(|c| self.is_free(BoardIndex::from_col_row(c, r))))
+    }
 }
 
-impl<T> SquareAccessor for T where T: IndexMut<BoardIndex, Output = SquareContent> {
+impl<T> SquareAccessor for T where T: IndexMut<BoardIndex, Output = SquareContent> + BoardDimensions {
     fn is_free(&self, ix: BoardIndex) -> bool {
         self[ix] == SquareContent::Water
     }
     fn set_ship(&mut self, ix: BoardIndex) {
         self[ix] = SquareContent::Ship;
     }
+    fn clear(&mut self, ix: BoardIndex) {
+        self[ix] = SquareContent::Water;
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -37,14 +51,38 @@ pub struct PlacementError {
     error_kind: ErrorKind,
 }
 
+impl PlacementError {
+    pub(crate) fn new(error_kind: ErrorKind) -> PlacementError {
+        PlacementError { error_kind }
+    }
+
+    pub fn error_kind(&self) -> ErrorKind {
+        self.error_kind
+    }
+}
+
+impl std::fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.error_kind {
+            ErrorKind::ShipTooLong => write!(f, "Ship is longer than the board"),
+            ErrorKind::OutOfBounds => write!(f, "Ship does not fit on the board at the given position"),
+            ErrorKind::BoardTooOccupied => write!(f, "Cannot position ships, board is too occupied"),
+            ErrorKind::EmptyShip => write!(f, "Ship needs at least one square"),
+        }
+    }
+}
+
+impl std::error::Error for PlacementError {}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ErrorKind {
     ShipTooLong,
     OutOfBounds,
     BoardTooOccupied,
+    EmptyShip,
 }
 
-pub trait ShipSetter {
+pub trait ShipSetter: BoardDimensions {
     fn can_place_ship(
         &self,
         ix: BoardIndex,
@@ -57,6 +95,190 @@ pub trait ShipSetter {
         ship_length: usize,
         direction: Direction,
     ) -> Result<bool, PlacementError>;
+    /// Turns the squares of a previously placed ship back into water
+    fn remove_ship(
+        &mut self,
+        ix: BoardIndex,
+        ship_length: usize,
+        direction: Direction,
+    ) -> Result<(), PlacementError>;
+
+    /// Like `can_place_ship`, but for a ship of any shape whose rectangle starts at `origin`
+    fn can_place_shape(&self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError>;
+    fn try_place_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError>;
+    fn remove_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<(), PlacementError>;
+}
+
+/// Defines whether ships may touch each other.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdjacencyRule {
+    /// Ships have to be separated by at least one square of water
+    #[default]
+    NoTouching,
+    /// Ships must not overlap, but they may touch each other
+    MayTouch,
+}
+
+// Note: Generic function with a `?Sized` bound. It can be called with
+//       trait objects (`dyn SquareAccessor`) as well as with concrete types.
+fn can_place<A: SquareAccessor + ?Sized>(
+    accessor: &A,
+    ix: BoardIndex,
+    ship_length: usize,
+    direction: Direction,
+    rule: AdjacencyRule,
+) -> Result<bool, PlacementError> {
+    let side_length = accessor.side_length();
+    check_bounds(side_length, ix, ship_length, direction)?;
+
+    // Note: Ships must not touch each other. Therefore, we check the squares
+    //       occupied by the ship plus a one-square border around it.
+    let border = match rule {
+        AdjacencyRule::NoTouching => 1,
+        AdjacencyRule::MayTouch => 0,
+    };
+    fn get_first(ix: usize, border: usize) -> usize {
+        // Note: saturating arithmetic stops at the numeric bounds instead of overflowing
+        ix.saturating_sub(border)
+    }
+    fn get_last(ix: usize, length: usize, border: usize, side_length: usize) -> usize {
+        // Note: `min` is available on all types implementing `Ord`
+        (ix + length - 1 + border).min(side_length - 1)
+    }
+
+    let (rows, cols) = match direction {
+        Direction::Horizontal => (1, ship_length),
+        Direction::Vertical => (ship_length, 1),
+    };
+    let first_check_row = get_first(ix.row(), border);
+    let last_check_row = get_last(ix.row(), rows, border, side_length);
+    let first_check_col = get_first(ix.column(), border);
+    let last_check_col = get_last(ix.column(), cols, border, side_length);
+
+    Ok(accessor.is_area_free(
+        BoardIndex::from_col_row(first_check_col, first_check_row),
+        BoardIndex::from_col_row(last_check_col, last_check_row),
+    ))
+}
+
+fn place<A: SquareAccessor + ?Sized>(
+    accessor: &mut A,
+    ix: BoardIndex,
+    ship_length: usize,
+    direction: Direction,
+    rule: AdjacencyRule,
+) -> Result<bool, PlacementError> {
+    match can_place(accessor, ix, ship_length, direction, rule) {
+        Ok(true) => {
+            let side_length = accessor.side_length();
+            let mut ix = ix;
+            for i in 0..ship_length {
+                accessor.set_ship(ix);
+                if i + 1 < ship_length {
+                    ix = ix.try_next(direction, side_length).unwrap();
+                }
+            }
+            Ok(true)
+        },
+        res => res
+    }
+}
+
+fn check_bounds(side_length: usize, ix: BoardIndex, ship_length: usize, direction: Direction) -> Result<(), PlacementError> {
+    if ship_length == 0 {
+        return Err(PlacementError { error_kind: ErrorKind::EmptyShip });
+    }
+
+    if ship_length > side_length {
+        return Err(PlacementError { error_kind: ErrorKind::ShipTooLong });
+    }
+
+    fn outside_bounds(start: usize, ship_length: usize, side_length: usize) -> bool {
+        start + ship_length > side_length
+    }
+    if outside_bounds(
+        match direction {
+            Direction::Vertical => ix.row(),
+            Direction::Horizontal => ix.column(),
+        },
+        ship_length,
+        side_length,
+    ) || ix.column() >= side_length || ix.row() >= side_length
+    {
+        return Err(PlacementError { error_kind: ErrorKind::OutOfBounds });
+    }
+
+    Ok(())
+}
+
+fn remove<A: SquareAccessor + ?Sized>(
+    accessor: &mut A,
+    ix: BoardIndex,
+    ship_length: usize,
+    direction: Direction,
+) -> Result<(), PlacementError> {
+    let side_length = accessor.side_length();
+    check_bounds(side_length, ix, ship_length, direction)?;
+    std::iter::successors(Some(ix), |i| i.try_next(direction, side_length))
+        .take(ship_length)
+        .for_each(|i| accessor.clear(i));
+    Ok(())
+}
+
+fn check_shape_bounds(side_length: usize, origin: BoardIndex, shape: &ShipShape) -> Result<(), PlacementError> {
+    if shape.width() > side_length || shape.height() > side_length {
+        return Err(PlacementError { error_kind: ErrorKind::ShipTooLong });
+    }
+
+    if origin.column() + shape.width() > side_length || origin.row() + shape.height() > side_length {
+        return Err(PlacementError { error_kind: ErrorKind::OutOfBounds });
+    }
+
+    Ok(())
+}
+
+fn can_place_shape<A: SquareAccessor + ?Sized>(
+    accessor: &A,
+    origin: BoardIndex,
+    shape: &ShipShape,
+    rule: AdjacencyRule,
+) -> Result<bool, PlacementError> {
+    let side_length = accessor.side_length();
+    check_shape_bounds(side_length, origin, shape)?;
+
+    // Note: Unlike straight ships, shapes do not cover their whole rectangle.
+    //       Therefore, we check the border around every single square.
+    let border = match rule {
+        AdjacencyRule::NoTouching => 1,
+        AdjacencyRule::MayTouch => 0,
+    };
+    Ok(shape.squares_at(origin).all(|ix| {
+        accessor.is_area_free(
+            BoardIndex::from_col_row(ix.column().saturating_sub(border), ix.row().saturating_sub(border)),
+            BoardIndex::from_col_row((ix.column() + border).min(side_length - 1), (ix.row() + border).min(side_length - 1)),
+        )
+    }))
+}
+
+fn place_shape<A: SquareAccessor + ?Sized>(
+    accessor: &mut A,
+    origin: BoardIndex,
+    shape: &ShipShape,
+    rule: AdjacencyRule,
+) -> Result<bool, PlacementError> {
+    let placeable = can_place_shape(accessor, origin, shape, rule)?;
+    if placeable {
+        shape.squares_at(origin).for_each(|ix| accessor.set_ship(ix));
+    }
+
+    Ok(placeable)
+}
+
+fn remove_shape<A: SquareAccessor + ?Sized>(accessor: &mut A, origin: BoardIndex, shape: &ShipShape) -> Result<(), PlacementError> {
+    check_shape_bounds(accessor.side_length(), origin, shape)?;
+    shape.squares_at(origin).for_each(|ix| accessor.clear(ix));
+    Ok(())
 }
 
 impl<T> ShipSetter for T where T: SquareAccessor {
@@ -66,64 +288,68 @@ impl<T> ShipSetter for T where T: SquareAccessor {
         ship_length: usize,
         direction: Direction,
     ) -> Result<bool, PlacementError> {
-        if ship_length > BOARD_SIDE_LENGTH {
-            return Err(PlacementError { error_kind: ErrorKind::ShipTooLong });
-        }
+        can_place(self, ix, ship_length, direction, AdjacencyRule::NoTouching)
+    }
 
-        fn outside_bounds(start: usize, ship_length: usize) -> bool {
-            start + ship_length > 10
-        }
-        if outside_bounds(
-            match direction {
-                Direction::Vertical => ix.row(),
-                Direction::Horizontal => ix.column(),
-            },
-            ship_length,
-        ) {
-            return Err(PlacementError { error_kind: ErrorKind::OutOfBounds });
-        }
+    fn try_place_ship(
+        &mut self,
+        ix: BoardIndex,
+        ship_length: usize,
+        direction: Direction,
+    ) -> Result<bool, PlacementError> {
+        place(self, ix, ship_length, direction, AdjacencyRule::NoTouching)
+    }
 
-        fn get_first(ix: usize) -> usize {
-            // Note: if expression (not statement)
-            if ix == 0 {
-                ix
-            } else {
-                ix - 1
-            }
-        }
-        fn get_elements_to_check_accross(ix: usize) -> usize {
-            // Note: if let expression (=pattern matching)
-            if let 0 | 9 = ix {
-                2
-            } else {
-                3
-            }
-        }
-        fn get_elements_to_check_alongside(ix: usize, ship_length: usize) -> usize {
-            ship_length + if ix == 0 || ix + ship_length == BOARD_SIDE_LENGTH { 1 } else { 2 }
-        }
+    fn remove_ship(
+        &mut self,
+        ix: BoardIndex,
+        ship_length: usize,
+        direction: Direction,
+    ) -> Result<(), PlacementError> {
+        remove(self, ix, ship_length, direction)
+    }
 
-        let number_of_rows_to_check = match direction {
-            Direction::Horizontal => get_elements_to_check_accross(ix.row()),
-            Direction::Vertical => get_elements_to_check_alongside(ix.row(), ship_length),
-        };
-        let number_of_cols_to_check = match direction {
-            Direction::Horizontal => get_elements_to_check_alongside(ix.column(), ship_length),
-            Direction::Vertical => get_elements_to_check_accross(ix.column()),
-        };
-        let first_check_row = get_first(ix.row());
-        let first_check_col = get_first(ix.column());
+    fn can_place_shape(&self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError> {
+        can_place_shape(self, origin, shape, AdjacencyRule::NoTouching)
+    }
 
-        // Discuss: ranges with 1..100 and 1..=100
-        for r in first_check_row..(first_check_row + number_of_rows_to_check) {
-            for c in first_check_col..(first_check_col + number_of_cols_to_check) {
-                if !self.is_free(BoardIndex::from_col_row(c, r)) {
-                    return Ok(false);
-                }
-            }
-        }
+    fn try_place_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError> {
+        place_shape(self, origin, shape, AdjacencyRule::NoTouching)
+    }
+
+    fn remove_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<(), PlacementError> {
+        remove_shape(self, origin, shape)
+    }
+}
+
+/// Ship setter that places ships on the wrapped board according to a given [`AdjacencyRule`].
+///
+/// Boards enforce [`AdjacencyRule::NoTouching`] when used as [`ShipSetter`] directly.
+pub struct RuleAwareSetter<'a, T: SquareAccessor + ?Sized> {
+    accessor: &'a mut T,
+    rule: AdjacencyRule,
+}
+
+impl<'a, T: SquareAccessor + ?Sized> RuleAwareSetter<'a, T> {
+    pub fn new(accessor: &'a mut T, rule: AdjacencyRule) -> Self {
+        RuleAwareSetter { accessor, rule }
+    }
+}
+
+impl<'a, T: SquareAccessor + ?Sized> BoardDimensions for RuleAwareSetter<'a, T> {
+    fn side_length(&self) -> usize {
+        self.accessor.side_length()
+    }
+}
 
-        Ok(true)
+impl<'a, T: SquareAccessor + ?Sized> ShipSetter for RuleAwareSetter<'a, T> {
+    fn can_place_ship(
+        &self,
+        ix: BoardIndex,
+        ship_length: usize,
+        direction: Direction,
+    ) -> Result<bool, PlacementError> {
+        can_place(self.accessor, ix, ship_length, direction, self.rule)
     }
 
     fn try_place_ship(
@@ -132,29 +358,61 @@ impl<T> ShipSetter for T where T: SquareAccessor {
         ship_length: usize,
         direction: Direction,
     ) -> Result<bool, PlacementError> {
-        match self.can_place_ship(ix, ship_length, direction) {
-            Ok(true) => {
-                let mut ix = ix;
-                for _ in 0..ship_length {
-                    self.set_ship(ix);
-                    ix = ix.try_next(direction).unwrap();
-                }
-                Ok(true)
-            },
-            res => res
-        }
+        place(self.accessor, ix, ship_length, direction, self.rule)
+    }
+
+    fn remove_ship(
+        &mut self,
+        ix: BoardIndex,
+        ship_length: usize,
+        direction: Direction,
+    ) -> Result<(), PlacementError> {
+        remove(self.accessor, ix, ship_length, direction)
+    }
+
+    fn can_place_shape(&self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError> {
+        can_place_shape(self.accessor, origin, shape, self.rule)
+    }
+
+    fn try_place_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError> {
+        place_shape(self.accessor, origin, shape, self.rule)
+    }
+
+    fn remove_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<(), PlacementError> {
+        remove_shape(self.accessor, origin, shape)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::GenericBoardContent;
+    use crate::{GenericBoardContent, BOARD_SIDE_LENGTH};
 
     use super::*;
+    use mockall::mock;
     use rstest::rstest;
 
+    // Note: `automock` cannot handle traits with supertraits. Therefore,
+    //       we have to use the `mock!` macro here.
+    mock! {
+        SquareAccessor {}
+        impl BoardDimensions for SquareAccessor {
+            fn side_length(&self) -> usize;
+        }
+        impl SquareAccessor for SquareAccessor {
+            fn is_free(&self, ix: BoardIndex) -> bool;
+            fn set_ship(&mut self, ix: BoardIndex);
+            fn clear(&mut self, ix: BoardIndex);
+        }
+    }
+
+    fn get_mock(side_length: usize) -> MockSquareAccessor {
+        let mut mock = MockSquareAccessor::new();
+        mock.expect_side_length().return_const(side_length);
+        mock
+    }
+
     fn get_dummy_free_checker() -> impl SquareAccessor {
-        MockSquareAccessor::new()
+        get_mock(BOARD_SIDE_LENGTH)
     }
 
     #[test]
@@ -196,7 +454,7 @@ mod tests {
     #[test]
     fn calls_is_free_correct_number_of_times()
     {
-        let mut mock = MockSquareAccessor::new();
+        let mut mock = get_mock(BOARD_SIDE_LENGTH);
         mock.expect_is_free().times((3 + 1) * 2).return_const(true);
         assert!(mock.can_place_ship(BoardIndex::new(), 3, Direction::Horizontal).unwrap());
     }
@@ -221,7 +479,7 @@ mod tests {
         #[case] min_row: usize,
         #[case] max_row: usize,
     ) {
-        let mut mock = MockSquareAccessor::new();
+        let mut mock = get_mock(BOARD_SIDE_LENGTH);
         mock.expect_is_free().returning(move |ix| { 
             // Discuss: Why can't we use a range pattern check here?
             assert!(ix.column() >= min_col && ix.column() <= max_col);
@@ -233,13 +491,39 @@ mod tests {
 
     #[test]
     fn can_place_ship_overlap() {
-        let mut mock = MockSquareAccessor::new();
+        let mut mock = get_mock(BOARD_SIDE_LENGTH);
         mock.expect_is_free().returning(|ix| { 
             !(ix.row() == 3 && ix.column() == 3)
         });
         assert!(!mock.can_place_ship(BoardIndex::from_col_row(3, 2), 3, Direction::Vertical).unwrap());
     }
 
+    #[rstest]
+    #[case(8, 0, 0, 8, Direction::Horizontal, true)]
+    #[case(8, 0, 0, 9, Direction::Horizontal, false)]
+    #[case(8, 7, 5, 3, Direction::Vertical, true)]
+    #[case(8, 7, 6, 3, Direction::Vertical, false)]
+    #[case(15, 10, 0, 5, Direction::Horizontal, true)]
+    #[case(15, 0, 11, 5, Direction::Vertical, false)]
+    fn bounds_depend_on_side_length(
+        #[case] side_length: usize,
+        #[case] col: usize,
+        #[case] row: usize,
+        #[case] ship_length: usize,
+        #[case] direction: Direction,
+        #[case] fits: bool,
+    ) {
+        let board = GenericBoardContent::new_initialized_with_side_length(SquareContent::Water, side_length);
+        assert_eq!(fits, board.can_place_ship(BoardIndex::from_col_row(col, row), ship_length, direction).is_ok());
+    }
+
+    #[test]
+    fn try_place_ship_at_border() {
+        let mut board = GenericBoardContent::new_initialized_with_side_length(SquareContent::Water, 8);
+        assert!(board.try_place_ship("H4".parse().unwrap(), 5, Direction::Vertical).unwrap());
+        assert_eq!(SquareContent::Ship, board[BoardIndex::from("H8")]);
+    }
+
     #[test]
     fn can_place_ship_on_board() {
         let board = GenericBoardContent::new_initialized(SquareContent::Water);
@@ -249,8 +533,8 @@ mod tests {
     #[test]
     fn try_place_ship_returns_error() {
         let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
-        board[BoardIndex::from_index(1)] = SquareContent::Ship;
-        assert!(!board.try_place_ship(0.into(), 2, Direction::Horizontal).unwrap());
+        board[BoardIndex::from_col_row(1, 0)] = SquareContent::Ship;
+        assert!(!board.try_place_ship(BoardIndex::new(), 2, Direction::Horizontal).unwrap());
     }
 
     #[rstest]
@@ -258,10 +542,112 @@ mod tests {
     #[case(Direction::Horizontal)]
     fn try_place_ship_horizontal(#[case] direction: Direction) {
         let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
-        assert!(board.try_place_ship(0.into(), 2, direction).unwrap());
-        assert_eq!(SquareContent::Ship, board[BoardIndex::from_index(0)]);
-        assert_eq!(SquareContent::Ship, board[BoardIndex::from_index(0).try_next(direction).unwrap()]);
+        assert!(board.try_place_ship(BoardIndex::new(), 2, direction).unwrap());
+        assert_eq!(SquareContent::Ship, board[BoardIndex::new()]);
+        assert_eq!(SquareContent::Ship, board[BoardIndex::new().try_next(direction, BOARD_SIDE_LENGTH).unwrap()]);
         assert_eq!(2, board.iter().filter(|s| { *s == SquareContent::Ship }).count());
         assert_eq!(98, board.into_iter().filter(|s| { *s == SquareContent::Water }).count());
     }
+
+    #[rstest]
+    #[case(AdjacencyRule::NoTouching, false)]
+    #[case(AdjacencyRule::MayTouch, true)]
+    fn rule_aware_setter_adjacent_ship(#[case] rule: AdjacencyRule, #[case] expected: bool) {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("A1".parse().unwrap(), 3, Direction::Horizontal).unwrap();
+
+        let mut setter = RuleAwareSetter::new(&mut board, rule);
+        assert_eq!(expected, setter.try_place_ship("A2".parse().unwrap(), 3, Direction::Horizontal).unwrap());
+    }
+
+    #[test]
+    fn rule_aware_setter_rejects_overlap() {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("A1".parse().unwrap(), 3, Direction::Horizontal).unwrap();
+
+        let setter = RuleAwareSetter::new(&mut board, AdjacencyRule::MayTouch);
+        assert!(!setter.can_place_ship("C1".parse().unwrap(), 3, Direction::Vertical).unwrap());
+    }
+
+    #[test]
+    fn rule_aware_setter_checks_bounds() {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        let setter = RuleAwareSetter::new(&mut board, AdjacencyRule::MayTouch);
+        assert_eq!(
+            ErrorKind::OutOfBounds,
+            setter.can_place_ship("J1".parse().unwrap(), 2, Direction::Horizontal).err().unwrap().error_kind
+        );
+    }
+
+    #[test]
+    fn remove_ship() {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("B2".parse().unwrap(), 3, Direction::Vertical).unwrap();
+        board.remove_ship("B2".parse().unwrap(), 3, Direction::Vertical).unwrap();
+        assert!(board.iter().all(|s| s == SquareContent::Water));
+    }
+
+    #[test]
+    fn remove_ship_out_of_bounds() {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        assert_eq!(
+            Err(PlacementError::new(ErrorKind::OutOfBounds)),
+            board.remove_ship("J1".parse().unwrap(), 2, Direction::Horizontal)
+        );
+    }
+
+    fn l_shape() -> ShipShape {
+        ShipShape::new([(0, 0), (0, 1), (0, 2), (1, 2)]).unwrap()
+    }
+
+    #[test]
+    fn place_shape() {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        assert!(board.try_place_shape("A1".parse().unwrap(), &l_shape()).unwrap());
+        assert_eq!(SquareContent::Ship, board[BoardIndex::from("B3")]);
+        assert_eq!(SquareContent::Water, board[BoardIndex::from("B1")]);
+
+        // Note: C1 is inside the border of the shape's rectangle, but does not touch the shape
+        assert!(board.can_place_shape("C1".parse().unwrap(), &ShipShape::straight(1, Direction::Horizontal)).unwrap());
+        assert!(!board.can_place_shape("C2".parse().unwrap(), &ShipShape::straight(1, Direction::Horizontal)).unwrap());
+
+        board.remove_shape("A1".parse().unwrap(), &l_shape()).unwrap();
+        assert!(board.iter().all(|s| s == SquareContent::Water));
+    }
+
+    #[rstest]
+    #[case(AdjacencyRule::NoTouching, false)]
+    #[case(AdjacencyRule::MayTouch, true)]
+    fn rule_aware_setter_adjacent_shape(#[case] rule: AdjacencyRule, #[case] expected: bool) {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_shape("A1".parse().unwrap(), &l_shape()).unwrap();
+
+        let mut setter = RuleAwareSetter::new(&mut board, rule);
+        assert_eq!(expected, setter.try_place_shape("B1".parse().unwrap(), &l_shape().mirrored().rotated()).unwrap());
+    }
+
+    #[test]
+    fn shape_out_of_bounds() {
+        let board = GenericBoardContent::new_initialized(SquareContent::Water);
+        assert_eq!(
+            Err(PlacementError::new(ErrorKind::OutOfBounds)),
+            board.can_place_shape("J1".parse().unwrap(), &l_shape())
+        );
+        assert_eq!(
+            Err(PlacementError::new(ErrorKind::ShipTooLong)),
+            board.can_place_shape("A1".parse().unwrap(), &ShipShape::straight(11, Direction::Vertical))
+        );
+    }
+
+    #[rstest]
+    #[case(AdjacencyRule::NoTouching)]
+    #[case(AdjacencyRule::MayTouch)]
+    fn empty_ship(#[case] rule: AdjacencyRule) {
+        let mut board = GenericBoardContent::new_initialized(SquareContent::Water);
+        let mut setter = RuleAwareSetter::new(&mut board, rule);
+        assert_eq!(
+            Err(PlacementError::new(ErrorKind::EmptyShip)),
+            setter.try_place_ship("A1".parse().unwrap(), 0, Direction::Horizontal)
+        );
+    }
 }
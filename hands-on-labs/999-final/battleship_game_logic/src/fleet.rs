@@ -0,0 +1,339 @@
+use std::{fmt, ops::Index};
+
+use crate::{
+    BattleshipBoardContent, BoardDimensions, BoardIndex, Direction, GenericBoardContent, PlacementError, ShapePlacement,
+    ShipPlacement, ShipSetter, ShipShape, SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Modelling domain objects instead of deriving them from raw data
+    * Enum variants with and without data in the same enum
+    * Wrapping a trait object to observe calls (decorator pattern)
+
+    Recommended readings for this module:
+
+    * Enums: https://doc.rust-lang.org/book/ch06-01-defining-an-enum.html
+    * Decorator pattern: https://en.wikipedia.org/wiki/Decorator_pattern
+*/
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShipKind {
+    Carrier,
+    Battleship,
+    Cruiser,
+    Submarine,
+    Destroyer,
+    /// Ship that is not part of the classic fleet
+    Other(usize),
+}
+
+impl ShipKind {
+    /// Names the ships of a fleet. Ships of the classic fleet get their classic names.
+    pub fn name_fleet(ship_lengths: impl IntoIterator<Item = usize>) -> Vec<ShipKind> {
+        // Note: The classic fleet contains two ships with a length of three
+        let mut ships_of_length_three = 0;
+        ship_lengths
+            .into_iter()
+            .map(|length| match length {
+                5 => ShipKind::Carrier,
+                4 => ShipKind::Battleship,
+                3 => {
+                    ships_of_length_three += 1;
+                    if ships_of_length_three % 2 == 1 { ShipKind::Cruiser } else { ShipKind::Submarine }
+                }
+                2 => ShipKind::Destroyer,
+                length => ShipKind::Other(length),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ShipKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShipKind::Other(length) => write!(f, "{}-square ship", length),
+            kind => write!(f, "{:?}", kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ship {
+    kind: ShipKind,
+    shape: ShapePlacement,
+    hits: usize,
+}
+
+impl Ship {
+    pub fn kind(&self) -> ShipKind {
+        self.kind
+    }
+
+    /// Position of the ship if it is straight
+    pub fn placement(&self) -> Option<ShipPlacement> {
+        self.shape.to_ship_placement()
+    }
+
+    pub fn shape(&self) -> &ShapePlacement {
+        &self.shape
+    }
+
+    /// Number of squares of the ship
+    pub fn length(&self) -> usize {
+        self.shape.shape.len()
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn is_sunk(&self) -> bool {
+        self.hits == self.length()
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = BoardIndex> + '_ {
+        self.shape.squares()
+    }
+
+    pub fn contains(&self, ix: BoardIndex) -> bool {
+        self.shape.contains(ix)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Fleet {
+    ships: Vec<Ship>,
+}
+
+impl Fleet {
+    pub fn new(placements: &[ShipPlacement]) -> Fleet {
+        Fleet::with_shapes(placements.iter().map(|p| ShapePlacement::from(*p)).collect())
+    }
+
+    /// Creates a fleet of ships with arbitrary shapes. Only straight ships get classic names.
+    pub fn with_shapes(shapes: Vec<ShapePlacement>) -> Fleet {
+        let mut straight_kinds = ShipKind::name_fleet(
+            shapes.iter().filter(|s| s.shape.is_straight()).map(|s| s.shape.len())
+        ).into_iter();
+        Fleet {
+            ships: shapes
+                .into_iter()
+                .map(|shape| {
+                    let kind = match shape.shape.is_straight() {
+                        true => straight_kinds.next().unwrap(),
+                        false => ShipKind::Other(shape.shape.len()),
+                    };
+                    Ship { kind, shape, hits: 0 }
+                })
+                .collect(),
+        }
+    }
+
+    /// Identifies the ships on a board.
+    ///
+    /// Squares of ships touching each other are split into straight ships. The result
+    /// is not necessarily the layout that has been placed.
+    pub fn from_board(board: &(impl Index<BoardIndex, Output = SquareContent> + BoardDimensions)) -> Fleet {
+        let side_length = board.side_length();
+        let mut visited = GenericBoardContent::<bool>::new_with_side_length(side_length);
+        let mut placements = Vec::new();
+        for ix in (0..board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+            if !board[ix].is_ship() || visited[ix] {
+                continue;
+            }
+
+            let run = |direction| {
+                std::iter::successors(Some(ix), |i| i.try_next(direction, side_length))
+                    .take_while(|i| board[*i].is_ship() && !visited[*i])
+                    .count()
+            };
+            let placement = match (run(Direction::Horizontal), run(Direction::Vertical)) {
+                (1, vertical) if vertical > 1 => ShipPlacement::new(ix, vertical, Direction::Vertical),
+                (horizontal, _) => ShipPlacement::new(ix, horizontal, Direction::Horizontal),
+            };
+
+            placement.squares().for_each(|i| visited[i] = true);
+            placements.push(placement);
+        }
+
+        Fleet::new(&placements)
+    }
+
+    pub fn ships(&self) -> &[Ship] {
+        &self.ships
+    }
+
+    pub fn ship_at(&self, ix: BoardIndex) -> Option<&Ship> {
+        self.ships.iter().find(|s| s.contains(ix))
+    }
+
+    /// Positions of the straight ships of the fleet
+    pub fn placements(&self) -> Vec<ShipPlacement> {
+        self.ships.iter().filter_map(Ship::placement).collect()
+    }
+
+    pub fn shapes(&self) -> Vec<ShapePlacement> {
+        self.ships.iter().map(|s| s.shape.clone()).collect()
+    }
+
+    /// Checks whether all ships have been sunk. An empty fleet can never be sunk.
+    pub fn all_sunk(&self) -> bool {
+        !self.ships.is_empty() && self.ships.iter().all(Ship::is_sunk)
+    }
+
+    /// Board with `Ship` squares for the fleet and `Water` everywhere else
+    pub fn to_board(&self, side_length: usize) -> BattleshipBoardContent {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, side_length);
+        self.ships.iter().flat_map(Ship::squares).for_each(|ix| board[ix] = SquareContent::Ship);
+        board
+    }
+
+    // Note: Callers have to make sure that every square is only hit once
+    pub(crate) fn hit(&mut self, ix: BoardIndex) -> Option<&Ship> {
+        let ship = self.ships.iter_mut().find(|s| s.contains(ix))?;
+        ship.hits += 1;
+        Some(ship)
+    }
+
+    pub(crate) fn undo_hit(&mut self, ix: BoardIndex) {
+        if let Some(ship) = self.ships.iter_mut().find(|s| s.contains(ix)) {
+            ship.hits -= 1;
+        }
+    }
+}
+
+/// Ship setter that remembers where ships have been placed
+pub(crate) struct PlacementRecorder<'a> {
+    setter: &'a mut dyn ShipSetter,
+    placements: Vec<ShapePlacement>,
+}
+
+impl<'a> PlacementRecorder<'a> {
+    pub(crate) fn new(setter: &'a mut dyn ShipSetter) -> Self {
+        PlacementRecorder { setter, placements: Vec::new() }
+    }
+
+    pub(crate) fn into_fleet(self) -> Fleet {
+        Fleet::with_shapes(self.placements)
+    }
+}
+
+impl<'a> BoardDimensions for PlacementRecorder<'a> {
+    fn side_length(&self) -> usize {
+        self.setter.side_length()
+    }
+}
+
+impl<'a> ShipSetter for PlacementRecorder<'a> {
+    fn can_place_ship(&self, ix: BoardIndex, ship_length: usize, direction: Direction) -> Result<bool, PlacementError> {
+        self.setter.can_place_ship(ix, ship_length, direction)
+    }
+
+    fn try_place_ship(&mut self, ix: BoardIndex, ship_length: usize, direction: Direction) -> Result<bool, PlacementError> {
+        let placed = self.setter.try_place_ship(ix, ship_length, direction)?;
+        if placed {
+            self.placements.push(ShipPlacement::new(ix, ship_length, direction).into());
+        }
+
+        Ok(placed)
+    }
+
+    fn remove_ship(&mut self, ix: BoardIndex, ship_length: usize, direction: Direction) -> Result<(), PlacementError> {
+        self.setter.remove_ship(ix, ship_length, direction)?;
+        let placement = ShapePlacement::from(ShipPlacement::new(ix, ship_length, direction));
+        self.placements.retain(|p| *p != placement);
+        Ok(())
+    }
+
+    fn can_place_shape(&self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError> {
+        self.setter.can_place_shape(origin, shape)
+    }
+
+    fn try_place_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<bool, PlacementError> {
+        let placed = self.setter.try_place_shape(origin, shape)?;
+        if placed {
+            self.placements.push(ShapePlacement::new(origin, shape.clone()));
+        }
+
+        Ok(placed)
+    }
+
+    fn remove_shape(&mut self, origin: BoardIndex, shape: &ShipShape) -> Result<(), PlacementError> {
+        self.setter.remove_shape(origin, shape)?;
+        let placement = ShapePlacement::new(origin, shape.clone());
+        self.placements.retain(|p| *p != placement);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AdjacencyRule, BoardFiller, RuleAwareSetter, CLASSIC_FLEET, backtracking_placer};
+
+    use super::*;
+
+    #[test]
+    fn classic_names() {
+        assert_eq!(
+            vec![ShipKind::Carrier, ShipKind::Battleship, ShipKind::Cruiser, ShipKind::Submarine, ShipKind::Destroyer],
+            ShipKind::name_fleet(CLASSIC_FLEET.iter().copied())
+        );
+        assert_eq!(vec![ShipKind::Other(1), ShipKind::Cruiser], ShipKind::name_fleet([1, 3]));
+        assert_eq!("Cruiser", ShipKind::Cruiser.to_string());
+        assert_eq!("6-square ship", ShipKind::Other(6).to_string());
+    }
+
+    #[test]
+    fn hit_and_sink() {
+        let mut fleet = Fleet::new(&[ShipPlacement::new("A1".into(), 2, Direction::Horizontal)]);
+        assert_eq!(None, fleet.hit("C1".into()));
+        assert!(!fleet.hit("A1".into()).unwrap().is_sunk());
+
+        let ship = fleet.hit("B1".into()).unwrap();
+        assert_eq!(ShipKind::Destroyer, ship.kind());
+        assert!(ship.is_sunk());
+        assert!(fleet.all_sunk());
+
+        fleet.undo_hit("B1".into());
+        assert!(!fleet.all_sunk());
+    }
+
+    #[test]
+    fn from_board() {
+        let placements = [
+            ShipPlacement::new("A1".into(), 3, Direction::Horizontal),
+            ShipPlacement::new("J2".into(), 4, Direction::Vertical),
+            ShipPlacement::new("E5".into(), 1, Direction::Horizontal),
+        ];
+        let board = Fleet::new(&placements).to_board(10);
+
+        let fleet = Fleet::from_board(&board);
+        assert_eq!(placements.to_vec(), fleet.placements());
+        assert_eq!(board, fleet.to_board(10));
+    }
+
+    #[test]
+    fn from_board_with_touching_ships() {
+        let placements = [
+            ShipPlacement::new("A1".into(), 3, Direction::Horizontal),
+            ShipPlacement::new("A2".into(), 3, Direction::Vertical),
+        ];
+        let board = Fleet::new(&placements).to_board(10);
+        assert_eq!(board, Fleet::from_board(&board).to_board(10));
+    }
+
+    #[test]
+    fn recorder() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        let mut setter = RuleAwareSetter::new(&mut board, AdjacencyRule::NoTouching);
+        let mut recorder = PlacementRecorder::new(&mut setter);
+        recorder.fill_with_seed(CLASSIC_FLEET, backtracking_placer, 42).unwrap();
+
+        let fleet = recorder.into_fleet();
+        assert_eq!(5, fleet.ships().len());
+        assert_eq!(board, fleet.to_board(10));
+    }
+}
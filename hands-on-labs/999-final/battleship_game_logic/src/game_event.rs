@@ -0,0 +1,56 @@
+use std::fmt;
+
+use crate::{BoardIndex, GameState, ShipKind};
+
+/*
+    Learnings in this module:
+
+    * Describing what happened with an enum instead of comparing snapshots
+    * Implementing `Display` for user-facing messages
+
+    Recommended readings for this module:
+
+    * Enums: https://doc.rust-lang.org/book/ch06-01-defining-an-enum.html
+    * `Display` trait: https://doc.rust-lang.org/std/fmt/trait.Display.html
+*/
+
+/// Something that happened because of a shot
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameEvent {
+    Miss(BoardIndex),
+    Hit(BoardIndex),
+    /// Follows the `Hit` event of the shot that sank the ship
+    Sunk { ship: ShipKind, squares: Vec<BoardIndex> },
+    /// Squares around a sunken ship that have been revealed as water (see `GameRules::reveal_water`)
+    WaterRevealed(Vec<BoardIndex>),
+    /// The square has been shot at before, nothing changed
+    RepeatShot(BoardIndex),
+    /// The shot ended the game
+    GameOver(GameState),
+}
+
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameEvent::Miss(ix) => write!(f, "{}: Miss", ix),
+            GameEvent::Hit(ix) => write!(f, "{}: Hit", ix),
+            GameEvent::Sunk { ship, .. } => write!(f, "You sank my {}", ship),
+            GameEvent::WaterRevealed(squares) => write!(f, "{} squares of water revealed", squares.len()),
+            GameEvent::RepeatShot(ix) => write!(f, "{}: Already shot at", ix),
+            GameEvent::GameOver(state) => write!(f, "Game over ({:?})", state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!("B3: Miss", GameEvent::Miss("B3".into()).to_string());
+        assert_eq!("You sank my Cruiser", GameEvent::Sunk { ship: ShipKind::Cruiser, squares: vec![] }.to_string());
+        assert_eq!("Game over (AllShipsSunken)", GameEvent::GameOver(GameState::AllShipsSunken).to_string());
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{AdjacencyRule, BOARD_SIDE_LENGTH};
+
+/*
+    Learnings in this module:
+
+    * Structs with public fields
+    * Struct update syntax
+    * Owned vs. borrowed data (`Vec<T>` vs. `&[T]`)
+
+    Recommended readings for this module:
+
+    * Struct update syntax: https://doc.rust-lang.org/book/ch05-01-defining-structs.html#creating-instances-from-other-instances-with-struct-update-syntax
+    * `Vec<T>`: https://doc.rust-lang.org/std/vec/struct.Vec.html
+*/
+
+/// Ships of the classic fleet (carrier, battleship, cruiser, submarine, destroyer)
+pub const CLASSIC_FLEET: &[usize] = &[5, 4, 3, 3, 2];
+
+/// Maximum number of shots in a classic game
+pub const CLASSIC_MAX_SHOTS: usize = 100;
+
+/// Defines how shots at squares that have already been shot at are treated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RepeatShotRule {
+    /// Repeat shots are logged and count towards the maximum number of shots
+    #[default]
+    Count,
+    /// `try_shoot` rejects repeat shots, they are not logged
+    Reject,
+}
+
+/// Defines how many shots a player fires per turn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FiringMode {
+    #[default]
+    OneShotPerTurn,
+    /// One shot per surviving ship of the shooting player. `max_shots` still counts single shots.
+    Salvo,
+}
+
+// Note: Fields are public so that callers can use struct update syntax to
+//       deviate from the classic rules (e.g. `GameRules { max_shots: 60, ..Default::default() }`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameRules {
+    pub side_length: usize,
+    pub ship_lengths: Vec<usize>,
+    /// Largest number of shots a player may fire. The game is lost once it is reached.
+    pub max_shots: usize,
+    pub adjacency: AdjacencyRule,
+    // Note: The following fields are optional in saved games and default to the classic rules
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub repeat_shots: RepeatShotRule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub firing_mode: FiringMode,
+    /// Marks all unknown squares around a sunken ship as water. Only applies if ships must not touch.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reveal_water: bool,
+}
+
+impl GameRules {
+    pub fn new() -> GameRules {
+        GameRules {
+            side_length: BOARD_SIDE_LENGTH,
+            ship_lengths: CLASSIC_FLEET.to_vec(),
+            max_shots: CLASSIC_MAX_SHOTS,
+            adjacency: AdjacencyRule::NoTouching,
+            repeat_shots: RepeatShotRule::Count,
+            firing_mode: FiringMode::OneShotPerTurn,
+            reveal_water: false,
+        }
+    }
+
+    /// Total number of squares occupied by the fleet
+    pub fn fleet_size(&self) -> usize {
+        self.ship_lengths.iter().sum()
+    }
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_rules() {
+        let rules = GameRules::default();
+        assert_eq!(BOARD_SIDE_LENGTH, rules.side_length);
+        assert_eq!(CLASSIC_FLEET, rules.ship_lengths.as_slice());
+        assert_eq!(CLASSIC_MAX_SHOTS, rules.max_shots);
+        assert_eq!(AdjacencyRule::NoTouching, rules.adjacency);
+        assert_eq!(RepeatShotRule::Count, rules.repeat_shots);
+        assert_eq!(FiringMode::OneShotPerTurn, rules.firing_mode);
+    }
+
+    #[test]
+    fn fleet_size() {
+        assert_eq!(17, GameRules::default().fleet_size());
+        assert_eq!(3, GameRules { ship_lengths: vec![2, 1], ..Default::default() }.fleet_size());
+    }
+}
@@ -1,6 +1,6 @@
-use std::{cmp::Ordering, convert::TryFrom, fmt, ops::{Index, IndexMut}};
+use std::{cmp::Ordering, convert::TryFrom, fmt, ops::{Index, IndexMut}, str::FromStr};
 
-use crate::{BOARD_SIDE_LENGTH, BOARD_SIZE, Row, RowsIterator, SquareContent};
+use crate::{BoardDimensions, BoardIndex, Column, ColumnsIterator, Diagonal, DiagonalDirection, DiagonalsIterator, Region, Row, RowsIterator, SquareContent, BOARD_SIDE_LENGTH, MAX_BOARD_SIDE_LENGTH, MIN_BOARD_SIDE_LENGTH};
 
 /*
     Learnings in this module:
@@ -19,6 +19,7 @@ use crate::{BOARD_SIDE_LENGTH, BOARD_SIZE, Row, RowsIterator, SquareContent};
     * Defining traits: https://doc.rust-lang.org/book/ch10-02-traits.html
     * `Display` trait: https://doc.rust-lang.org/std/fmt/trait.Display.html
     * `TryFrom` and `TryInto` traits: https://doc.rust-lang.org/rust-by-example/conversion/try_from_try_into.html
+    * `FromStr` trait: https://doc.rust-lang.org/std/str/trait.FromStr.html
     * `impl` trait: https://doc.rust-lang.org/rust-by-example/trait/impl_trait.html
     * `debug_assert` marco: https://doc.rust-lang.org/std/macro.debug_assert.html
     * Unit struct: https://doc.rust-lang.org/rust-by-example/custom_types/structs.html
@@ -26,9 +27,20 @@ use crate::{BOARD_SIDE_LENGTH, BOARD_SIZE, Row, RowsIterator, SquareContent};
 
 pub type BattleshipBoardContent = GenericBoardContent<SquareContent>;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(
+    into = "crate::serialization::SavedBoard<T>",
+    try_from = "crate::serialization::SavedBoard<T>",
+    bound(serialize = "T: serde::Serialize + Clone", deserialize = "T: serde::Deserialize<'de>"),
+))]
 pub struct GenericBoardContent<T> {
-    board_content: [T; BOARD_SIZE],
+    side_length: usize,
+    board_content: Vec<T>,
+}
+
+fn is_valid_side_length(side_length: usize) -> bool {
+    (MIN_BOARD_SIDE_LENGTH..=MAX_BOARD_SIDE_LENGTH).contains(&side_length)
 }
 
 impl<T: Default + Copy> GenericBoardContent<T> {
@@ -37,17 +49,30 @@ impl<T: Default + Copy> GenericBoardContent<T> {
     }
 
     pub fn new_initialized(initial_content: T) -> GenericBoardContent<T> {
+        GenericBoardContent::new_initialized_with_side_length(initial_content, BOARD_SIDE_LENGTH)
+    }
+
+    pub fn new_with_side_length(side_length: usize) -> Self {
+        GenericBoardContent::new_initialized_with_side_length(Default::default(), side_length)
+    }
+
+    pub fn new_initialized_with_side_length(initial_content: T, side_length: usize) -> GenericBoardContent<T> {
+        if !is_valid_side_length(side_length) {
+            panic!("Invalid board side length");
+        }
+
         GenericBoardContent {
-            board_content: [initial_content; BOARD_SIZE],
+            side_length,
+            board_content: vec![initial_content; side_length * side_length],
         }
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = T> {
-        self.board_content.into_iter()
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.board_content.iter().copied()
     }
 
-    pub fn row(&self, row: usize) -> Row<T> {
-        if row >= BOARD_SIDE_LENGTH {
+    pub fn row(&self, row: usize) -> Row<'_, T> {
+        if row >= self.side_length {
             panic!("Index out of bounds");
         }
 
@@ -58,10 +83,52 @@ impl<T: Default + Copy> GenericBoardContent<T> {
         &self.board_content
     }
 
-    pub fn rows(&self) -> impl Iterator<Item = Row<T>> {
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_, T>> {
         RowsIterator::new(self)
     }
 
+    pub fn column(&self, column: usize) -> Column<'_, T> {
+        if column >= self.side_length {
+            panic!("Index out of bounds");
+        }
+
+        Column::new(self, column)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = Column<'_, T>> {
+        ColumnsIterator::new(self)
+    }
+
+    /// Diagonal in the given direction that runs through `ix`
+    pub fn diagonal(&self, ix: BoardIndex, direction: DiagonalDirection) -> Diagonal<'_, T> {
+        if !self.contains(ix) {
+            panic!("Index out of bounds");
+        }
+
+        Diagonal::new(self, ix, direction)
+    }
+
+    pub fn diagonals(&self, direction: DiagonalDirection) -> impl Iterator<Item = Diagonal<'_, T>> {
+        DiagonalsIterator::new(self, direction)
+    }
+
+    /// Rectangle spanned by two opposite corners. Corners outside of the board are moved onto the board.
+    pub fn region(&self, a: BoardIndex, b: BoardIndex) -> Region<'_, T> {
+        let last = self.side_length - 1;
+        let first = BoardIndex::from_col_row(a.column().min(b.column()).min(last), a.row().min(b.row()).min(last));
+        let last = BoardIndex::from_col_row(a.column().max(b.column()).min(last), a.row().max(b.row()).min(last));
+        Region::new(self, first, last)
+    }
+
+    /// Squares at most `radius` rows and columns away from `ix` (including `ix`)
+    pub fn region_around(&self, ix: BoardIndex, radius: usize) -> Region<'_, T> {
+        let last = MAX_BOARD_SIDE_LENGTH - 1;
+        self.region(
+            BoardIndex::from_col_row(ix.column().saturating_sub(radius), ix.row().saturating_sub(radius)),
+            BoardIndex::from_col_row(ix.column().saturating_add(radius).min(last), ix.row().saturating_add(radius).min(last)),
+        )
+    }
+
     pub fn board_buffer(&self) -> *const T {
         self.board_content.as_ptr()
     }
@@ -74,20 +141,25 @@ pub trait ToCompactString {
 impl<T: Copy + Into<char>> ToCompactString for GenericBoardContent<T> {
     fn to_compact_str(&self) -> String {
         let mut result = String::new();
-        result.reserve_exact(BOARD_SIZE);
+        result.reserve_exact(self.board_content.len());
 
-        // Note iterating over array, brand new in Rust 1.53
-        for c in self.board_content {
+        for c in self.board_content.iter().copied() {
             result.push(c.into());
         }
         result
     }
 }
 
+impl<T> BoardDimensions for GenericBoardContent<T> {
+    fn side_length(&self) -> usize {
+        self.side_length
+    }
+}
+
 impl<T> IntoIterator for GenericBoardContent<T> {
     type Item = T;
 
-    type IntoIter = core::array::IntoIter<Self::Item, BOARD_SIZE>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.board_content.into_iter()
@@ -100,6 +172,17 @@ impl<T: Default + Copy> Default for GenericBoardContent<T> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T> GenericBoardContent<T> {
+    pub(crate) fn from_squares(side_length: usize, squares: Vec<T>) -> Result<Self, InvalidBoardSize> {
+        if !is_valid_side_length(side_length) || squares.len() != side_length * side_length {
+            return Err(InvalidBoardSize);
+        }
+
+        Ok(GenericBoardContent { side_length, board_content: squares })
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct InvalidBoardSize; // Note unit struct (field-less struct)
 
@@ -107,38 +190,76 @@ impl fmt::Display for InvalidBoardSize {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Board data is of invalid size. Has to contain {size} elements",
-            size = BOARD_SIZE
+            "Board data is of invalid size. Has to contain n*n elements with n between {min} and {max}",
+            min = MIN_BOARD_SIDE_LENGTH,
+            max = MAX_BOARD_SIDE_LENGTH
         )
     }
 }
 
-impl<T: From<u8> + Default + Copy> TryFrom<&[u8]> for GenericBoardContent<T> {
-    type Error = InvalidBoardSize;
+impl std::error::Error for InvalidBoardSize {}
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        match bytes.len() {
-            BOARD_SIZE => {
-                let mut content = GenericBoardContent::<T>::new();
-                for (ix, square_byte) in bytes.iter().copied().enumerate() {
-                    content.board_content[ix] = T::from(square_byte);
-                }
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseBoardError {
+    InvalidSize(InvalidBoardSize),
+    /// The square at the given offset (in bytes or characters) is invalid
+    InvalidSquare { offset: usize },
+}
 
-                Ok(content)
-            }
-            _ => Err(InvalidBoardSize),
-        }
+impl From<InvalidBoardSize> for ParseBoardError {
+    fn from(e: InvalidBoardSize) -> Self {
+        ParseBoardError::InvalidSize(e)
     }
 }
 
-impl<T: Default + Into<u8> + Copy> From<GenericBoardContent<T>> for [u8; BOARD_SIZE] {
-    fn from(c: GenericBoardContent<T>) -> Self {
-        let mut content: [u8; BOARD_SIZE] = [T::default().into(); BOARD_SIZE];
-        for (ix, square) in c.board_content.iter().copied().enumerate() {
-            content[ix] = T::into(square);
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseBoardError::InvalidSize(e) => write!(f, "{}", e),
+            ParseBoardError::InvalidSquare { offset } => write!(f, "Invalid square content at offset {}", offset),
         }
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+fn parse_squares<T: Default + Copy, S: Copy>(
+    squares: &[S],
+    convert: impl Fn(S) -> Option<T>,
+) -> Result<GenericBoardContent<T>, ParseBoardError> {
+    // Note: `find` on a range returns the first number matching the predicate
+    let side_length = (MIN_BOARD_SIDE_LENGTH..=MAX_BOARD_SIDE_LENGTH)
+        .find(|l| l * l == squares.len())
+        .ok_or(InvalidBoardSize)?;
+
+    let mut content = GenericBoardContent::<T>::new_with_side_length(side_length);
+    for (offset, square) in squares.iter().copied().enumerate() {
+        content.board_content[offset] = convert(square).ok_or(ParseBoardError::InvalidSquare { offset })?;
+    }
+
+    Ok(content)
+}
+
+impl<T: TryFrom<u8> + Default + Copy> TryFrom<&[u8]> for GenericBoardContent<T> {
+    type Error = ParseBoardError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        parse_squares(bytes, |b| T::try_from(b).ok())
+    }
+}
+
+/// Parses the output of `to_compact_str`. Offsets in errors are counted in characters.
+impl<T: TryFrom<char> + Default + Copy> FromStr for GenericBoardContent<T> {
+    type Err = ParseBoardError;
 
-        content
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_squares(&s.chars().collect::<Vec<_>>(), |c| T::try_from(c).ok())
+    }
+}
+
+impl<T: Into<u8> + Copy> From<GenericBoardContent<T>> for Vec<u8> {
+    fn from(c: GenericBoardContent<T>) -> Self {
+        c.board_content.iter().copied().map(T::into).collect()
     }
 }
 
@@ -146,7 +267,7 @@ impl<T> Index<usize> for GenericBoardContent<T> {
     type Output = T;
 
     fn index(&self, ix: usize) -> &Self::Output {
-        match ix.cmp(&BOARD_SIZE) {
+        match ix.cmp(&self.board_content.len()) {
             Ordering::Greater | Ordering::Equal => panic!("Index out of bounds"),
             _ => &self.board_content[ix],
         }
@@ -155,7 +276,7 @@ impl<T> Index<usize> for GenericBoardContent<T> {
 
 impl<T> IndexMut<usize> for GenericBoardContent<T> {
     fn index_mut(&mut self, ix: usize) -> &mut Self::Output {
-        if ix >= BOARD_SIZE {
+        if ix >= self.board_content.len() {
             panic!("Index out of bounds");
         }
 
@@ -165,19 +286,19 @@ impl<T> IndexMut<usize> for GenericBoardContent<T> {
 
 impl<T: Default + Copy + Into<char>> fmt::Display for GenericBoardContent<T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fn build_separator(chars: &[char]) -> String {
+        fn build_separator(chars: &[char], side_length: usize) -> String {
             debug_assert!(chars.len() == 4);
 
             let mut result = String::new();
             result.reserve_exact(
                 chars[0].len_utf8()
-                    + BOARD_SIDE_LENGTH * 2 * chars[1].len_utf8()
-                    + (BOARD_SIDE_LENGTH - 1) * chars[2].len_utf8()
+                    + side_length * 2 * chars[1].len_utf8()
+                    + (side_length - 1) * chars[2].len_utf8()
                     + chars[3].len_utf8()
                     + '\n'.len_utf8(),
             );
             result.push(chars[0]);
-            for _ in 0..BOARD_SIDE_LENGTH - 1 {
+            for _ in 0..side_length - 1 {
                 result.push(chars[1]);
                 result.push(chars[1]);
                 result.push(chars[2]);
@@ -192,38 +313,39 @@ impl<T: Default + Copy + Into<char>> fmt::Display for GenericBoardContent<T> {
             result
         }
 
-        let top = build_separator(&['┏', '━', '┯', '┓']);
-        let middle = build_separator(&['┠', '─', '┼', '┨']);
-        let bottom = build_separator(&['┗', '━', '┷', '┛']);
+        let side_length = self.side_length;
+        let top = build_separator(&['┏', '━', '┯', '┓'], side_length);
+        let middle = build_separator(&['┠', '─', '┼', '┨'], side_length);
+        let bottom = build_separator(&['┗', '━', '┷', '┛'], side_length);
 
         let mut result = String::new();
         result.reserve_exact(
             top.len()
-                + middle.len() * (BOARD_SIDE_LENGTH - 1)
+                + middle.len() * (side_length - 1)
                 + bottom.len()
                 + (2 * '┃'.len_utf8()
-                    + (BOARD_SIDE_LENGTH - 1) * '|'.len_utf8()
-                    + BOARD_SIDE_LENGTH * 2 * ' '.len_utf8()
+                    + (side_length - 1) * '|'.len_utf8()
+                    + side_length * 2 * ' '.len_utf8()
                     + '\n'.len_utf8())
-                    * BOARD_SIDE_LENGTH,
+                    * side_length,
         );
 
         result.push_str(&top);
 
         for row in self.rows() {
             result.push('┃');
-            for col in 0..BOARD_SIDE_LENGTH {
+            for col in 0..side_length {
                 let char = row[col].into();
                 result.push(char);
                 result.push(char);
-                if col < 9 {
+                if col < side_length - 1 {
                     result.push('|');
                 }
             }
 
             result.push('┃');
             result.push('\n');
-            if row.row_index < 9 {
+            if row.has_next() {
                 result.push_str(&middle);
             }
         }
@@ -236,7 +358,7 @@ impl<T: Default + Copy + Into<char>> fmt::Display for GenericBoardContent<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::SquareContent;
+    use crate::{SquareContent, BOARD_SIZE};
 
     use super::*;
 
@@ -255,6 +377,20 @@ mod tests {
         assert!(b.into_iter().all(|v| v == square_content));
     }
 
+    #[test]
+    fn new_with_side_length() {
+        let b = BattleshipBoardContent::new_with_side_length(15);
+        assert_eq!(15, b.side_length());
+        assert_eq!(225, b.iter().count());
+        assert_eq!(15, b.rows().count());
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_with_invalid_side_length() {
+        BattleshipBoardContent::new_with_side_length(MAX_BOARD_SIDE_LENGTH + 1);
+    }
+
     #[test]
     fn clone() {
         let b = BattleshipBoardContent::new_initialized(SquareContent::SunkenShip);
@@ -275,21 +411,36 @@ mod tests {
     fn from_invalid_bytes() {
         let content: &[u8] = &[0; 99];
         let board = BattleshipBoardContent::try_from(content);
-        assert_eq!(Some(InvalidBoardSize), board.err());
+        assert_eq!(Some(ParseBoardError::InvalidSize(InvalidBoardSize)), board.err());
+    }
+
+    #[test]
+    fn from_bytes_with_invalid_square() {
+        let mut content = [0; 16];
+        content[5] = 42;
+        let board = BattleshipBoardContent::try_from(&content[..]);
+        assert_eq!(Some(ParseBoardError::InvalidSquare { offset: 5 }), board.err());
+    }
+
+    #[test]
+    fn from_bytes_with_side_length() {
+        let content: &[u8] = &[SquareContent::Water.into(); 64];
+        let board = BattleshipBoardContent::try_from(content).unwrap();
+        assert_eq!(8, board.side_length());
     }
 
     #[test]
     fn into_bytes() {
         let board = BattleshipBoardContent::new();
-        let bytes: [u8; BOARD_SIZE] = board.into();
-        assert_eq!(bytes.iter().count(), 100);
-        assert!(bytes.iter().all(|v| *v == SquareContent::default().into()));
+        let bytes: Vec<u8> = board.into();
+        assert_eq!(bytes.len(), 100);
+        assert!(bytes.iter().all(|v| *v == u8::from(SquareContent::default())));
     }
 
     #[test]
     #[should_panic]
     fn indexing_out_of_bounds() {
-        BattleshipBoardContent::new()[100];
+        let _ = BattleshipBoardContent::new()[100];
     }
 
     #[test]
@@ -316,7 +467,15 @@ mod tests {
     #[test]
     fn into_string() {
         let b = BattleshipBoardContent::new();
-        format!("{}", b);
+        assert!(!format!("{}", b).is_empty());
+    }
+
+    #[test]
+    fn into_string_with_side_length() {
+        let b = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 8);
+        let s = format!("{}", b);
+        assert_eq!(8 * 2 + 1, s.lines().count());
+        assert_eq!(8 * 2, s.lines().nth(1).unwrap().matches('~').count());
     }
 
     #[test]
@@ -324,4 +483,26 @@ mod tests {
         let b = BattleshipBoardContent::new();
         assert_eq!(format!("{: >100}", ""), b.to_compact_str());
     }
+
+    #[test]
+    fn parse_compact_str() {
+        let mut b = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 8);
+        b[3] = SquareContent::Ship;
+        b[10] = SquareContent::HitShip;
+        b[63] = SquareContent::Unknown;
+        assert_eq!(Ok(b.clone()), b.to_compact_str().parse());
+    }
+
+    #[test]
+    fn parse_invalid_str() {
+        assert_eq!(
+            Err(ParseBoardError::InvalidSquare { offset: 2 }),
+            "~~?~".parse::<BattleshipBoardContent>()
+        );
+        assert_eq!(
+            Err(ParseBoardError::InvalidSize(InvalidBoardSize)),
+            "~~~".parse::<BattleshipBoardContent>()
+        );
+        assert_eq!("Invalid square content at offset 2", "~~?~".parse::<BattleshipBoardContent>().unwrap_err().to_string());
+    }
 }
@@ -0,0 +1,227 @@
+use std::{fmt, ops::Index};
+
+use crate::{
+    BoardDimensions, BoardIndex, Direction, ShapeFindingResult, ShipFinder, ShipPlacement,
+    SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Validating untrusted input and reporting precise errors
+    * Using a trait (`ShipFinder`) through a generic bound
+    * Multiset difference with vectors
+
+    Recommended readings for this module:
+
+    * Trait bounds: https://doc.rust-lang.org/book/ch10-02-traits.html#trait-bound-syntax
+    * Error handling: https://doc.rust-lang.org/book/ch09-00-error-handling.html
+*/
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// Squares that are neither `Water` nor `Ship` (e.g. `HitShip` or `Unknown`)
+    InvalidSquares(Vec<BoardIndex>),
+    /// Squares of ships that touch each other (or of a ship that is not straight)
+    TouchingShips(Vec<BoardIndex>),
+    /// A ship whose length is not needed by the fleet. `missing` contains the lengths that are missing instead.
+    WrongLength { squares: Vec<BoardIndex>, missing: Vec<usize> },
+    /// A ship that is not part of the fleet
+    ExtraShip(Vec<BoardIndex>),
+    /// Lengths of the ships that are not on the board
+    MissingShips(Vec<usize>),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn join(squares: &[BoardIndex]) -> String {
+            squares.iter().map(|ix| ix.to_string()).collect::<Vec<_>>().join(", ")
+        }
+
+        match self {
+            LayoutError::InvalidSquares(squares) => write!(f, "Invalid squares: {}", join(squares)),
+            LayoutError::TouchingShips(squares) => {
+                write!(f, "Ships touch each other at {}", join(squares))
+            }
+            LayoutError::WrongLength { squares, missing } => {
+                write!(
+                    f,
+                    "Ship at {} has a wrong length, missing ships: {:?}",
+                    join(squares),
+                    missing
+                )
+            }
+            LayoutError::ExtraShip(squares) => {
+                write!(f, "Ship at {} is not part of the fleet", join(squares))
+            }
+            LayoutError::MissingShips(lengths) => write!(f, "Missing ships: {:?}", lengths),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Checks that the board contains exactly the ships of the given lengths and that they do not touch each other.
+/// Returns the positions of the ships in board order.
+pub fn validate_layout(
+    board: &(impl Index<BoardIndex, Output = SquareContent> + BoardDimensions),
+    ship_lengths: &[usize],
+) -> Result<Vec<ShipPlacement>, LayoutError> {
+    let side_length = board.side_length();
+    let all_squares = || (0..board.size()).map(|ix| BoardIndex::from_index(ix, side_length));
+
+    let invalid = all_squares()
+        .filter(|ix| !matches!(board[*ix], SquareContent::Water | SquareContent::Ship))
+        .collect::<Vec<_>>();
+    if !invalid.is_empty() {
+        return Err(LayoutError::InvalidSquares(invalid));
+    }
+
+    // Note: All squares are known, therefore every ship found is complete
+    let mut ships: Vec<Vec<BoardIndex>> = Vec::new();
+    for ix in all_squares() {
+        if ships.iter().any(|s| s.contains(&ix)) {
+            continue;
+        }
+
+        if let ShapeFindingResult::CompleteShip(squares) = board.try_find_shape(ix) {
+            let straight = squares.iter().all(|s| s.row() == squares[0].row())
+                || squares.iter().all(|s| s.column() == squares[0].column());
+            if !straight {
+                return Err(LayoutError::TouchingShips(squares));
+            }
+
+            ships.push(squares);
+        }
+    }
+
+    for (i, ship) in ships.iter().enumerate() {
+        for other in ships[i + 1..].iter() {
+            if ship.iter().any(|a| other.iter().any(|b| a.chebyshev_distance(*b) <= 1)) {
+                let mut squares = [ship.as_slice(), other.as_slice()].concat();
+                squares.sort();
+                return Err(LayoutError::TouchingShips(squares));
+            }
+        }
+    }
+
+    let mut missing = ship_lengths.to_vec();
+    let mut extra = Vec::new();
+    for ship in ships.iter() {
+        match missing.iter().position(|l| *l == ship.len()) {
+            Some(pos) => {
+                missing.remove(pos);
+            }
+            None => extra.push(ship),
+        }
+    }
+
+    missing.sort_unstable_by(|a, b| b.cmp(a));
+    match (extra.first(), missing.is_empty()) {
+        (Some(ship), false) => {
+            return Err(LayoutError::WrongLength { squares: ship.to_vec(), missing })
+        }
+        (Some(ship), true) => return Err(LayoutError::ExtraShip(ship.to_vec())),
+        (None, false) => return Err(LayoutError::MissingShips(missing)),
+        (None, true) => {}
+    }
+
+    Ok(ships
+        .iter()
+        .map(|squares| match squares.len() > 1 && squares[0].column() == squares[1].column() {
+            true => ShipPlacement::new(squares[0], squares.len(), Direction::Vertical),
+            false => ShipPlacement::new(squares[0], squares.len(), Direction::Horizontal),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BattleshipBoardContent, ShipSetter, CLASSIC_FLEET};
+
+    use super::*;
+
+    fn squares(squares: &[&str]) -> Vec<BoardIndex> {
+        squares.iter().map(|s| BoardIndex::from(*s)).collect()
+    }
+
+    fn get_board() -> BattleshipBoardContent {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("A1".into(), 3, Direction::Horizontal).unwrap();
+        board.try_place_ship("E1".into(), 2, Direction::Vertical).unwrap();
+        board
+    }
+
+    #[test]
+    fn valid_layout() {
+        assert_eq!(
+            Ok(vec![
+                ShipPlacement::new("A1".into(), 3, Direction::Horizontal),
+                ShipPlacement::new("E1".into(), 2, Direction::Vertical),
+            ]),
+            validate_layout(&get_board(), &[2, 3])
+        );
+    }
+
+    #[test]
+    fn valid_classic_layout() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("B2".into(), 5, Direction::Vertical).unwrap();
+        board.try_place_ship("D2".into(), 4, Direction::Horizontal).unwrap();
+        board.try_place_ship("J1".into(), 3, Direction::Vertical).unwrap();
+        board.try_place_ship("D8".into(), 3, Direction::Horizontal).unwrap();
+        board.try_place_ship("H10".into(), 2, Direction::Horizontal).unwrap();
+        assert_eq!(5, validate_layout(&board, CLASSIC_FLEET).unwrap().len());
+    }
+
+    #[test]
+    fn invalid_squares() {
+        let mut board = get_board();
+        board[BoardIndex::from("B1")] = SquareContent::HitShip;
+        board[BoardIndex::from("J10")] = SquareContent::Unknown;
+        assert_eq!(
+            Err(LayoutError::InvalidSquares(squares(&["B1", "J10"]))),
+            validate_layout(&board, &[2, 3])
+        );
+    }
+
+    #[test]
+    fn touching_ships() {
+        let mut board = get_board();
+        board[BoardIndex::from("D2")] = SquareContent::Ship;
+        assert_eq!(
+            Err(LayoutError::TouchingShips(squares(&["E1", "D2", "E2"]))),
+            validate_layout(&board, &[2, 3])
+        );
+
+        let mut board = get_board();
+        board[BoardIndex::from("D3")] = SquareContent::Ship;
+        assert_eq!(
+            Err(LayoutError::TouchingShips(squares(&["E1", "E2", "D3"]))),
+            validate_layout(&board, &[2, 3, 1])
+        );
+    }
+
+    #[test]
+    fn wrong_length() {
+        assert_eq!(
+            Err(LayoutError::WrongLength {
+                squares: squares(&["A1", "B1", "C1"]),
+                missing: vec![4]
+            }),
+            validate_layout(&get_board(), &[4, 2])
+        );
+    }
+
+    #[test]
+    fn extra_and_missing_ships() {
+        assert_eq!(
+            Err(LayoutError::ExtraShip(squares(&["E1", "E2"]))),
+            validate_layout(&get_board(), &[3])
+        );
+        assert_eq!(
+            Err(LayoutError::MissingShips(vec![4, 2])),
+            validate_layout(&get_board(), &[2, 4, 3, 2])
+        );
+    }
+}
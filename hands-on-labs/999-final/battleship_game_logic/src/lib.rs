@@ -5,29 +5,75 @@ mod square_content;
 mod simple_board_content;
 mod generic_board_content;
 mod row;
+mod column;
+mod diagonal;
+mod region;
 mod board_index;
 mod fillable_board;
 mod board_filler;
 mod single_player_game;
 mod board_index_range;
 mod ship_finder;
+mod game_rules;
+mod two_player_game;
+mod shot_strategy;
+mod probability_density;
+mod placement_session;
+mod replay;
+mod statistics;
+mod fleet;
+mod game_event;
+mod ship_shape;
+mod layout_validation;
+mod placement_enumeration;
+mod bitboard;
+mod simulation;
+mod placement_strategy;
+mod bimaru;
+// Note: Conditional compilation, the module only exists if the `serde` feature is enabled
+#[cfg(feature = "serde")]
+mod serialization;
 
-// Note use of consts here. This has been done for demo purposes only.
-// The algorithms in this package are NOT capable of handling
-// a board side length other than 10. Such a feature would make 
-// algorithms more compilicated -> out of scope of the workshop.
-const BOARD_SIDE_LENGTH: usize = 10;
+// Note use of consts here. Boards can have any side length between
+// MIN_BOARD_SIDE_LENGTH and MAX_BOARD_SIDE_LENGTH. The side length is chosen
+// when a board is created. Boards created with `new` use the classic 10x10 layout.
+pub const BOARD_SIDE_LENGTH: usize = 10;
 pub const BOARD_SIZE: usize = BOARD_SIDE_LENGTH * BOARD_SIDE_LENGTH;
 
+// Note: The upper limit results from the way squares are named (A1..Z26).
+pub const MIN_BOARD_SIDE_LENGTH: usize = 1;
+pub const MAX_BOARD_SIDE_LENGTH: usize = 26;
+
 // Note: Re-exports the content of the square_content module to keep paths short.
 //       Read more at https://doc.rust-lang.org/reference/items/use-declarations.html#use-visibility
 pub use crate::square_content::*;
 pub use crate::simple_board_content::*;
 pub use crate::generic_board_content::*;
 pub use crate::row::*;
+pub use crate::column::*;
+pub use crate::diagonal::*;
+pub use crate::region::*;
 pub use crate::board_index::*;
 pub use crate::fillable_board::*;
 pub use crate::board_filler::*;
 pub use crate::single_player_game::*;
 pub use crate::board_index_range::*;
 pub use crate::ship_finder::*;
+pub use crate::game_rules::*;
+pub use crate::two_player_game::*;
+pub use crate::shot_strategy::*;
+pub use crate::probability_density::*;
+pub use crate::placement_session::*;
+pub use crate::replay::*;
+pub use crate::statistics::*;
+pub use crate::fleet::*;
+pub use crate::game_event::*;
+pub use crate::ship_shape::*;
+pub use crate::layout_validation::*;
+pub use crate::placement_enumeration::*;
+pub use crate::bitboard::*;
+pub use crate::simulation::*;
+pub use crate::placement_strategy::*;
+pub use crate::bimaru::*;
+#[cfg(feature = "serde")]
+pub use crate::serialization::*;
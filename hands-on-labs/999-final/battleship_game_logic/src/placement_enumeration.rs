@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+
+use crate::{AdjacencyRule, BoardIndex, Direction, InvalidBoardSize, ShipPlacement, ShipSetter};
+
+/*
+    Learnings in this module:
+
+    * Custom iterators that keep their state in a struct
+    * Turning a recursive search into an iterator with an explicit stack
+    * Restoring state with the `Drop` trait
+    * Bit manipulation with `u128` masks
+    * Using symmetries to reduce the search space
+
+    Recommended readings for this module:
+
+    * Iterators: https://doc.rust-lang.org/std/iter/index.html#implementing-iterator
+    * `Drop` trait: https://doc.rust-lang.org/std/ops/trait.Drop.html
+    * Bit manipulation: https://doc.rust-lang.org/std/primitive.u128.html#method.count_ones
+    * Symmetries of a square: https://en.wikipedia.org/wiki/Dihedral_group
+*/
+
+/// Iterator over all positions at which a ship fits on the board of a [`ShipSetter`].
+///
+/// Ships of length one are only returned in horizontal direction.
+pub struct ShipPlacements<'a, S: ShipSetter + ?Sized> {
+    setter: &'a S,
+    ship_length: usize,
+    // Note: Every square is tried twice, first horizontally and then vertically
+    next: usize,
+}
+
+/// Enumerates all positions at which a ship of the given length can be placed.
+///
+/// The setter decides which squares are free, so this works on empty boards
+/// as well as on boards that already contain ships.
+pub fn ship_placements<S: ShipSetter + ?Sized>(
+    setter: &S,
+    ship_length: usize,
+) -> ShipPlacements<'_, S> {
+    ShipPlacements { setter, ship_length, next: 0 }
+}
+
+impl<'a, S: ShipSetter + ?Sized> Iterator for ShipPlacements<'a, S> {
+    type Item = (BoardIndex, Direction);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let side_length = self.setter.side_length();
+        while self.next < side_length * side_length * 2 {
+            let ix = BoardIndex::from_index(self.next / 2, side_length);
+            let direction = [Direction::Horizontal, Direction::Vertical][self.next % 2];
+            self.next += 1;
+
+            if self.ship_length == 1 && direction == Direction::Vertical {
+                continue;
+            }
+
+            if let Ok(true) = self.setter.can_place_ship(ix, self.ship_length, direction) {
+                return Some((ix, direction));
+            }
+        }
+
+        None
+    }
+}
+
+/// Iterator over all layouts of a fleet on the board of a [`ShipSetter`].
+///
+/// Layouts that only differ in the order of ships with equal length are returned once.
+/// Ships are placed on the board while iterating. They are removed again when the
+/// iterator is exhausted or dropped.
+pub struct FleetLayouts<'a, S: ShipSetter + ?Sized> {
+    setter: &'a mut S,
+    ships: Vec<usize>,
+    candidates: Vec<Vec<(BoardIndex, Direction)>>,
+    // Note: Index of the next candidate to try for every ship
+    cursors: Vec<usize>,
+    placed: Vec<ShipPlacement>,
+    yielded: bool,
+    finished: bool,
+}
+
+/// Enumerates all layouts of the given ships that fit on the board.
+pub fn fleet_layouts<'a, S: ShipSetter + ?Sized>(
+    setter: &'a mut S,
+    ship_lengths: &[usize],
+) -> FleetLayouts<'a, S> {
+    // Note: Long ships have fewer options, so placing them first leads to dead ends earlier
+    let mut ships = ship_lengths.to_vec();
+    ships.sort_unstable_by(|a, b| b.cmp(a));
+    let candidates = ships.iter().map(|ship| ship_placements(&*setter, *ship).collect()).collect();
+    FleetLayouts {
+        setter,
+        cursors: vec![0; ships.len()],
+        ships,
+        candidates,
+        placed: Vec::new(),
+        yielded: false,
+        finished: false,
+    }
+}
+
+impl<'a, S: ShipSetter + ?Sized> FleetLayouts<'a, S> {
+    fn remove_last(&mut self) {
+        if let Some(p) = self.placed.pop() {
+            self.setter.remove_ship(p.start, p.ship_length, p.direction).unwrap();
+        }
+    }
+}
+
+impl<'a, S: ShipSetter + ?Sized> Iterator for FleetLayouts<'a, S> {
+    type Item = Vec<ShipPlacement>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.yielded {
+            self.remove_last();
+            self.yielded = false;
+        }
+
+        while !self.finished {
+            let level = self.placed.len();
+            if level == self.ships.len() {
+                // Note: Without ships, there is exactly one (empty) layout
+                self.finished = self.ships.is_empty();
+                self.yielded = true;
+                return Some(self.placed.clone());
+            }
+
+            let ship = self.ships[level];
+            let mut found = false;
+            while let Some((ix, direction)) =
+                self.candidates[level].get(self.cursors[level]).copied()
+            {
+                self.cursors[level] += 1;
+                if let Ok(true) = self.setter.try_place_ship(ix, ship, direction) {
+                    self.placed.push(ShipPlacement::new(ix, ship, direction));
+
+                    // Note: Equal ships are placed in the order of their candidates to avoid duplicate layouts
+                    if let Some(next_ship) = self.ships.get(level + 1) {
+                        self.cursors[level + 1] =
+                            if *next_ship == ship { self.cursors[level] } else { 0 };
+                    }
+
+                    found = true;
+                    break;
+                }
+            }
+
+            if !found {
+                if level == 0 {
+                    self.finished = true;
+                } else {
+                    self.remove_last();
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, S: ShipSetter + ?Sized> Drop for FleetLayouts<'a, S> {
+    fn drop(&mut self) {
+        while !self.placed.is_empty() {
+            self.remove_last();
+        }
+    }
+}
+
+/// Largest side length supported by [`count_fleet_layouts`] (every square needs a bit in a `u128`)
+pub const MAX_COUNTING_SIDE_LENGTH: usize = 11;
+
+/// Counts all layouts of the given ships on an empty board.
+///
+/// Like [`fleet_layouts`], layouts that only differ in the order of ships with equal length are counted once.
+///
+/// Much faster than counting [`fleet_layouts`] because it works on bit masks, counts the
+/// positions of the last ship without placing it, and only places the first ship at one
+/// position per group of positions that are equal except for rotating or mirroring the board.
+///
+/// Returns an error for boards larger than [`MAX_COUNTING_SIDE_LENGTH`].
+pub fn count_fleet_layouts(
+    side_length: usize,
+    ship_lengths: &[usize],
+    rule: AdjacencyRule,
+) -> Result<u64, InvalidBoardSize> {
+    if side_length > MAX_COUNTING_SIDE_LENGTH {
+        return Err(InvalidBoardSize);
+    }
+
+    let mut ships = ship_lengths.to_vec();
+    ships.sort_unstable_by(|a, b| b.cmp(a));
+    if ships.is_empty() {
+        return Ok(1);
+    }
+
+    let counter = LayoutCounter::new(side_length, &ships, rule);
+
+    // Note: Rotating or mirroring a layout results in another valid layout. Therefore, all positions
+    //       of the first ship that are symmetric to each other have the same number of layouts.
+    let mut groups: HashMap<u128, (usize, u64)> = HashMap::new();
+    for (i, (ship, _)) in counter.candidates[0].iter().enumerate() {
+        let key = counter.symmetries(*ship).into_iter().min().unwrap();
+        groups.entry(key).or_insert((i, 0)).1 += 1;
+    }
+
+    let total: u64 = groups
+        .values()
+        .map(|(i, size)| size * counter.count(1, counter.candidates[0][*i].1, 0))
+        .sum();
+
+    // Note: The first ship is not ordered relative to other ships of the same length,
+    //       so every layout has been counted once for each of them.
+    Ok(total / ships.iter().filter(|s| **s == ships[0]).count() as u64)
+}
+
+/// Maps (column, row) to the square it ends up on for a given side length
+type SquareTransform = fn(usize, usize, usize) -> (usize, usize);
+
+struct LayoutCounter {
+    side_length: usize,
+    ships: Vec<usize>,
+    // Note: Squares of the ship and squares blocked by the ship (including the border for `NoTouching`)
+    candidates: Vec<Vec<(u128, u128)>>,
+    board: u128,
+    // Note: Squares at which a horizontal ship of the last length fits into its row
+    last_ship_starts: u128,
+}
+
+impl LayoutCounter {
+    fn new(side_length: usize, ships: &[usize], rule: AdjacencyRule) -> LayoutCounter {
+        let bit = |c: usize, r: usize| 1u128 << (r * side_length + c);
+        let border = match rule {
+            AdjacencyRule::NoTouching => 1,
+            AdjacencyRule::MayTouch => 0,
+        };
+
+        let candidates = ships
+            .iter()
+            .map(|ship| {
+                let mut result = Vec::new();
+                for ix in
+                    (0..side_length * side_length).map(|ix| BoardIndex::from_index(ix, side_length))
+                {
+                    for (direction, cols, rows) in
+                        [(Direction::Horizontal, *ship, 1), (Direction::Vertical, 1, *ship)]
+                    {
+                        if (*ship == 1 && direction == Direction::Vertical)
+                            || ix.column() + cols > side_length
+                            || ix.row() + rows > side_length
+                        {
+                            continue;
+                        }
+
+                        let mut mask = 0;
+                        let mut zone = 0;
+                        for r in ix.row().saturating_sub(border)
+                            ..(ix.row() + rows + border).min(side_length)
+                        {
+                            for c in ix.column().saturating_sub(border)
+                                ..(ix.column() + cols + border).min(side_length)
+                            {
+                                zone |= bit(c, r);
+                                if (ix.row()..ix.row() + rows).contains(&r)
+                                    && (ix.column()..ix.column() + cols).contains(&c)
+                                {
+                                    mask |= bit(c, r);
+                                }
+                            }
+                        }
+
+                        result.push((mask, zone));
+                    }
+                }
+
+                result
+            })
+            .collect();
+
+        let board = (0..side_length * side_length).fold(0, |board, ix| board | 1u128 << ix);
+        let last_ship = ships[ships.len() - 1];
+        let last_ship_starts = (0..side_length * side_length)
+            .filter(|ix| ix % side_length + last_ship <= side_length)
+            .fold(0, |mask, ix| mask | 1u128 << ix);
+        LayoutCounter { side_length, ships: ships.to_vec(), candidates, board, last_ship_starts }
+    }
+
+    /// All eight variants of a mask that result from rotating and mirroring the board
+    fn symmetries(&self, mask: u128) -> Vec<u128> {
+        let s = self.side_length;
+        let transforms: [SquareTransform; 8] = [
+            |c, r, _| (c, r),
+            |c, r, s| (s - 1 - c, r),
+            |c, r, s| (c, s - 1 - r),
+            |c, r, s| (s - 1 - c, s - 1 - r),
+            |c, r, _| (r, c),
+            |c, r, s| (s - 1 - r, c),
+            |c, r, s| (r, s - 1 - c),
+            |c, r, s| (s - 1 - r, s - 1 - c),
+        ];
+
+        transforms
+            .iter()
+            .map(|t| {
+                (0..s * s).filter(|ix| mask & 1 << ix != 0).fold(0, |result, ix| {
+                    let (c, r) = t(ix % s, ix / s, s);
+                    result | 1u128 << (r * s + c)
+                })
+            })
+            .collect()
+    }
+
+    fn count(&self, level: usize, blocked: u128, first_candidate: usize) -> u64 {
+        if level == self.ships.len() {
+            return 1;
+        }
+
+        let ship = self.ships[level];
+        if level + 1 == self.ships.len() && first_candidate == 0 {
+            return self.count_last_ship(self.board & !blocked);
+        }
+
+        let mut result = 0;
+        for (i, (mask, zone)) in self.candidates[level].iter().enumerate().skip(first_candidate) {
+            if mask & blocked == 0 {
+                let next_first_candidate =
+                    if self.ships.get(level + 1) == Some(&ship) { i + 1 } else { 0 };
+                result += self.count(level + 1, blocked | zone, next_first_candidate);
+            }
+        }
+
+        result
+    }
+
+    /// Number of positions of the last ship within the free squares
+    fn count_last_ship(&self, free: u128) -> u64 {
+        let ship = self.ships[self.ships.len() - 1];
+        let s = self.side_length;
+
+        // Note: A bit remains set if the ship fits when starting at this square
+        let horizontal = (1..ship).fold(free & self.last_ship_starts, |fits, i| fits & free >> i);
+        if ship == 1 {
+            return horizontal.count_ones() as u64;
+        }
+
+        let vertical = (1..ship).fold(free, |fits, i| fits & free >> (i * s));
+        (horizontal.count_ones() + vertical.count_ones()) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{BattleshipBoardContent, RuleAwareSetter, SquareContent, CLASSIC_FLEET};
+
+    use super::*;
+
+    fn empty_board(side_length: usize) -> BattleshipBoardContent {
+        BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, side_length)
+    }
+
+    #[test]
+    fn placements_on_empty_board() {
+        let board = empty_board(10);
+        assert_eq!(2 * 7 * 10, ship_placements(&board, 4).count());
+        assert_eq!(100, ship_placements(&board, 1).count());
+        assert_eq!(0, ship_placements(&board, 11).count());
+    }
+
+    #[test]
+    fn placements_on_partially_filled_board() {
+        let mut board = empty_board(3);
+        board.try_place_ship("A1".into(), 1, Direction::Horizontal).unwrap();
+        assert_eq!(
+            vec![
+                (BoardIndex::from("C1"), Direction::Vertical),
+                (BoardIndex::from("C2"), Direction::Vertical),
+                (BoardIndex::from("A3"), Direction::Horizontal),
+                (BoardIndex::from("B3"), Direction::Horizontal),
+            ],
+            ship_placements(&board, 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn layouts_are_unique_and_board_is_restored() {
+        let mut board = empty_board(4);
+        let layouts = fleet_layouts(&mut board, &[2, 2]).collect::<Vec<_>>();
+        assert!(layouts.iter().all(|l| l.len() == 2));
+        for (i, layout) in layouts.iter().enumerate() {
+            assert!(!layouts[i + 1..].iter().any(|l| l[0] == layout[1] && l[1] == layout[0]));
+        }
+        assert_eq!(board, empty_board(4));
+
+        // Note: Dropping the iterator removes the ships of the current layout
+        assert!(fleet_layouts(&mut board, &[3, 2]).next().is_some());
+        assert_eq!(board, empty_board(4));
+    }
+
+    #[test]
+    fn layouts_without_ships() {
+        assert_eq!(
+            vec![Vec::<ShipPlacement>::new()],
+            fleet_layouts(&mut empty_board(3), &[]).collect::<Vec<_>>()
+        );
+        assert_eq!(0, fleet_layouts(&mut empty_board(3), &[4]).count());
+    }
+
+    #[rstest]
+    #[case(5, &[3, 2, 2])]
+    #[case(6, &[3, 3, 2])]
+    #[case(6, &[2, 2, 2, 1])]
+    #[case(5, &[1])]
+    fn count_matches_enumeration(#[case] side_length: usize, #[case] ships: &[usize]) {
+        let mut board = empty_board(side_length);
+        let expected = fleet_layouts(&mut board, ships).count() as u64;
+        assert_eq!(
+            Ok(expected),
+            count_fleet_layouts(side_length, ships, AdjacencyRule::NoTouching)
+        );
+    }
+
+    #[rstest]
+    #[case(4, &[2, 2])]
+    #[case(5, &[3, 2, 2, 1])]
+    fn count_may_touch_matches_enumeration(#[case] side_length: usize, #[case] ships: &[usize]) {
+        let mut board = empty_board(side_length);
+        let mut setter = RuleAwareSetter::new(&mut board, AdjacencyRule::MayTouch);
+        let expected = fleet_layouts(&mut setter, ships).count() as u64;
+        assert_eq!(Ok(expected), count_fleet_layouts(side_length, ships, AdjacencyRule::MayTouch));
+    }
+
+    #[test]
+    fn count_classic_fleet() {
+        assert_eq!(
+            Ok(1_925_751_392),
+            count_fleet_layouts(10, CLASSIC_FLEET, AdjacencyRule::NoTouching)
+        );
+        // Note: Often quoted as 30,093,975,536, which counts both ships of length 3 as different ships
+        assert_eq!(
+            Ok(15_046_987_768),
+            count_fleet_layouts(10, CLASSIC_FLEET, AdjacencyRule::MayTouch)
+        );
+    }
+
+    #[rstest]
+    #[case(MAX_COUNTING_SIDE_LENGTH + 1)]
+    #[case(26)]
+    fn count_too_large_board(#[case] side_length: usize) {
+        assert_eq!(
+            Err(InvalidBoardSize),
+            count_fleet_layouts(side_length, &[2], AdjacencyRule::NoTouching)
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{
+    AdjacencyRule, BattleshipBoardContent, BoardDimensions, BoardIndex, Direction, Fleet, GameRules, PlacementError,
+    RuleAwareSetter, ShipSetter, SinglePlayerGame, SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Enums with struct-like variants carrying data
+    * Converting errors with `From` so that `?` can be used
+    * Multiset operations on vectors
+
+    Recommended readings for this module:
+
+    * Enum variants with data: https://doc.rust-lang.org/book/ch06-01-defining-an-enum.html
+    * `?` operator and `From`: https://doc.rust-lang.org/book/ch09-02-recoverable-errors-with-result.html#a-shortcut-for-propagating-errors-the--operator
+*/
+
+/// Position and orientation of a single ship on the board
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShipPlacement {
+    pub start: BoardIndex,
+    pub ship_length: usize,
+    pub direction: Direction,
+}
+
+impl ShipPlacement {
+    pub fn new(start: BoardIndex, ship_length: usize, direction: Direction) -> ShipPlacement {
+        ShipPlacement { start, ship_length, direction }
+    }
+
+    /// Squares covered by the ship
+    pub fn squares(&self) -> impl Iterator<Item = BoardIndex> {
+        let (start, direction) = (self.start, self.direction);
+        (0..self.ship_length).map(move |i| match direction {
+            Direction::Horizontal => BoardIndex::from_col_row(start.column() + i, start.row()),
+            Direction::Vertical => BoardIndex::from_col_row(start.column(), start.row() + i),
+        })
+    }
+
+    pub fn contains(&self, ix: BoardIndex) -> bool {
+        self.squares().any(|i| i == ix)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    /// The ship does not fit on the board at the given position
+    Placement(PlacementError),
+    /// All ships with the given length have already been placed (or the fleet has no such ship)
+    ShipNotInFleet(usize),
+    /// The ship would overlap (`colliding`) or touch (`touching`) the given squares of other ships
+    Blocked { colliding: Vec<BoardIndex>, touching: Vec<BoardIndex> },
+    /// There is no ship on the given square
+    NoShipAt(BoardIndex),
+    /// The game cannot be started before all ships have been placed
+    FleetIncomplete { unplaced: Vec<usize> },
+}
+
+impl From<PlacementError> for SessionError {
+    fn from(e: PlacementError) -> Self {
+        SessionError::Placement(e)
+    }
+}
+
+impl std::fmt::Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn join(squares: &[BoardIndex]) -> String {
+            squares.iter().map(|ix| ix.to_string()).collect::<Vec<_>>().join(", ")
+        }
+
+        match self {
+            SessionError::Placement(e) => write!(f, "{}", e),
+            SessionError::ShipNotInFleet(length) => write!(f, "There is no unplaced ship with length {}", length),
+            SessionError::Blocked { colliding, touching } if touching.is_empty() => {
+                write!(f, "Ship collides with {}", join(colliding))
+            }
+            SessionError::Blocked { colliding, touching } if colliding.is_empty() => {
+                write!(f, "Ship touches {}", join(touching))
+            }
+            SessionError::Blocked { colliding, touching } => {
+                write!(f, "Ship collides with {} and touches {}", join(colliding), join(touching))
+            }
+            SessionError::NoShipAt(ix) => write!(f, "There is no ship at {}", ix),
+            SessionError::FleetIncomplete { unplaced } => write!(f, "Ships not placed yet: {:?}", unplaced),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+/// Lets a player place their fleet ship by ship.
+#[derive(Debug, Clone)]
+pub struct PlacementSession {
+    rules: GameRules,
+    board: BattleshipBoardContent,
+    placements: Vec<ShipPlacement>,
+}
+
+impl PlacementSession {
+    pub fn new(rules: GameRules) -> PlacementSession {
+        PlacementSession {
+            board: BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, rules.side_length),
+            rules,
+            placements: Vec::new(),
+        }
+    }
+
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    pub fn board(&self) -> &BattleshipBoardContent {
+        &self.board
+    }
+
+    pub fn placements(&self) -> &[ShipPlacement] {
+        &self.placements
+    }
+
+    /// Lengths of the ships that still have to be placed (longest first)
+    pub fn unplaced_ships(&self) -> Vec<usize> {
+        let mut unplaced = self.rules.ship_lengths.clone();
+        for placement in self.placements.iter() {
+            if let Some(pos) = unplaced.iter().position(|l| *l == placement.ship_length) {
+                unplaced.remove(pos);
+            }
+        }
+
+        unplaced.sort_unstable_by(|a, b| b.cmp(a));
+        unplaced
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.placements.len() == self.rules.ship_lengths.len()
+    }
+
+    pub fn place(&mut self, ship_length: usize, ix: BoardIndex, direction: Direction) -> Result<ShipPlacement, SessionError> {
+        if !self.unplaced_ships().contains(&ship_length) {
+            return Err(SessionError::ShipNotInFleet(ship_length));
+        }
+
+        let mut setter = RuleAwareSetter::new(&mut self.board, self.rules.adjacency);
+        if !setter.try_place_ship(ix, ship_length, direction)? {
+            return Err(self.blocked(ShipPlacement::new(ix, ship_length, direction)));
+        }
+
+        let placement = ShipPlacement::new(ix, ship_length, direction);
+        self.placements.push(placement);
+        Ok(placement)
+    }
+
+    /// Removes the ship covering the given square
+    pub fn remove(&mut self, ix: BoardIndex) -> Result<ShipPlacement, SessionError> {
+        let pos = self.placements.iter().position(|p| p.contains(ix)).ok_or(SessionError::NoShipAt(ix))?;
+        let placement = self.placements.remove(pos);
+        self.board.remove_ship(placement.start, placement.ship_length, placement.direction)?;
+        Ok(placement)
+    }
+
+    /// Creates a game with the placed fleet once all ships have been placed
+    pub fn start_game(&self) -> Result<SinglePlayerGame, SessionError> {
+        if !self.is_complete() {
+            return Err(SessionError::FleetIncomplete { unplaced: self.unplaced_ships() });
+        }
+
+        Ok(SinglePlayerGame::with_fleet(Fleet::new(&self.placements), self.rules.clone()))
+    }
+
+    fn blocked(&self, placement: ShipPlacement) -> SessionError {
+        let side_length = self.board.side_length();
+        let colliding = placement.squares().filter(|ix| self.board[*ix] == SquareContent::Ship).collect::<Vec<_>>();
+
+        let mut touching = Vec::new();
+        if self.rules.adjacency == AdjacencyRule::NoTouching {
+            for ix in placement.squares().flat_map(|square| square.neighbors8(side_length)) {
+                if self.board[ix] == SquareContent::Ship && !placement.contains(ix) && !touching.contains(&ix) {
+                    touching.push(ix);
+                }
+            }
+        }
+
+        touching.sort();
+        SessionError::Blocked { colliding, touching }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ErrorKind, GameState};
+
+    use super::*;
+
+    fn get_session() -> PlacementSession {
+        PlacementSession::new(GameRules { ship_lengths: vec![3, 2, 2], ..Default::default() })
+    }
+
+    #[test]
+    fn place_and_start_game() {
+        let mut session = get_session();
+        session.place(3, "A1".into(), Direction::Horizontal).unwrap();
+        session.place(2, "A3".into(), Direction::Vertical).unwrap();
+        assert_eq!(vec![2], session.unplaced_ships());
+        assert!(!session.is_complete());
+
+        session.place(2, "J9".into(), Direction::Vertical).unwrap();
+        assert!(session.is_complete());
+
+        let mut game = session.start_game().unwrap();
+        assert_eq!(SquareContent::HitShip, game.shoot("B1".into()));
+        assert_eq!(GameState::InProgress, game.game_state());
+    }
+
+    #[test]
+    fn incomplete_fleet() {
+        let mut session = get_session();
+        session.place(2, "A1".into(), Direction::Horizontal).unwrap();
+        assert_eq!(
+            SessionError::FleetIncomplete { unplaced: vec![3, 2] },
+            session.start_game().err().unwrap()
+        );
+    }
+
+    #[test]
+    fn ship_not_in_fleet() {
+        let mut session = get_session();
+        assert_eq!(Err(SessionError::ShipNotInFleet(4)), session.place(4, "A1".into(), Direction::Horizontal));
+
+        session.place(3, "A1".into(), Direction::Horizontal).unwrap();
+        assert_eq!(Err(SessionError::ShipNotInFleet(3)), session.place(3, "A5".into(), Direction::Horizontal));
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut session = get_session();
+        let err = session.place(3, "I1".into(), Direction::Horizontal).err().unwrap();
+        assert!(matches!(err, SessionError::Placement(e) if e.error_kind() == ErrorKind::OutOfBounds));
+    }
+
+    #[test]
+    fn report_colliding_and_touching_squares() {
+        let mut session = get_session();
+        session.place(3, "B2".into(), Direction::Horizontal).unwrap();
+
+        let err = session.place(2, "C1".into(), Direction::Vertical).err().unwrap();
+        assert_eq!(
+            SessionError::Blocked { colliding: vec!["C2".into()], touching: vec!["B2".into(), "D2".into()] },
+            err
+        );
+
+        let err = session.place(2, "E3".into(), Direction::Horizontal).err().unwrap();
+        assert_eq!(SessionError::Blocked { colliding: vec![], touching: vec!["D2".into()] }, err);
+        assert_eq!("Ship touches D2", err.to_string());
+    }
+
+    #[test]
+    fn ships_may_touch() {
+        let mut session = PlacementSession::new(GameRules { adjacency: AdjacencyRule::MayTouch, ..get_session().rules });
+        session.place(3, "B2".into(), Direction::Horizontal).unwrap();
+        session.place(2, "B3".into(), Direction::Horizontal).unwrap();
+
+        let err = session.place(2, "C1".into(), Direction::Vertical).err().unwrap();
+        assert_eq!(SessionError::Blocked { colliding: vec!["C2".into()], touching: vec![] }, err);
+    }
+
+    #[test]
+    fn remove() {
+        let mut session = get_session();
+        session.place(3, "B2".into(), Direction::Horizontal).unwrap();
+        assert_eq!(Err(SessionError::NoShipAt("A1".into())), session.remove("A1".into()));
+
+        let removed = session.remove("D2".into()).unwrap();
+        assert_eq!(ShipPlacement::new("B2".into(), 3, Direction::Horizontal), removed);
+        assert!(session.board().iter().all(|s| s == SquareContent::Water));
+        assert_eq!(vec![3, 2, 2], session.unplaced_ships());
+
+        session.place(2, "C1".into(), Direction::Vertical).unwrap();
+    }
+
+    #[test]
+    fn placement_squares() {
+        let placement = ShipPlacement::new("B2".into(), 3, Direction::Vertical);
+        assert_eq!(
+            vec![BoardIndex::from("B2"), BoardIndex::from("B3"), BoardIndex::from("B4")],
+            placement.squares().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,350 @@
+use rand::{Rng, RngCore};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    backtracking_placer, placement_rng, ship_placements, BoardDimensions, BoardIndex, HeatMap,
+    PlacementError, ShipPlacement, ShipPlacer, ShipSetter,
+};
+
+/*
+    Learnings in this module:
+
+    * Traits for stateful strategies (configuration and RNG live in the struct)
+    * Adapting function pointers to a trait
+    * Weighted random selection
+    * Borrowing different fields of a struct at the same time
+
+    Recommended readings for this module:
+
+    * Trait objects: https://doc.rust-lang.org/book/ch17-02-trait-objects.html
+    * Borrowing fields: https://doc.rust-lang.org/nomicon/borrow-splitting.html
+    * Weighted random sampling: https://en.wikipedia.org/wiki/Fitness_proportionate_selection
+*/
+
+/// Places all ships of a fleet on the board.
+///
+/// Unlike [`ShipPlacer`] functions, strategies own their configuration and random number generator.
+pub trait PlacementStrategy {
+    fn place(
+        &mut self,
+        ship_lengths: &[usize],
+        setter: &mut dyn ShipSetter,
+    ) -> Result<(), PlacementError>;
+}
+
+/// Turns a [`ShipPlacer`] function (e.g. [`crate::random_placer`]) into a [`PlacementStrategy`]
+pub struct SeededPlacer {
+    placer: ShipPlacer,
+    rng: ChaCha8Rng,
+}
+
+impl SeededPlacer {
+    pub fn new(placer: ShipPlacer, seed: u64) -> SeededPlacer {
+        SeededPlacer { placer, rng: placement_rng(seed) }
+    }
+}
+
+impl PlacementStrategy for SeededPlacer {
+    fn place(
+        &mut self,
+        ship_lengths: &[usize],
+        setter: &mut dyn ShipSetter,
+    ) -> Result<(), PlacementError> {
+        (self.placer)(ship_lengths, setter, &mut self.rng)
+    }
+}
+
+// Note: Weights only influence the random choice. If they lead into a dead end (e.g. clustering
+//       on a crowded board), we start over a few times before falling back to a complete search.
+const MAX_WEIGHTED_ATTEMPTS: usize = 20;
+
+/// Places the ships one after the other (longest first). Every position is picked
+/// with a probability proportional to its weight. `weight` gets the ships placed so far.
+fn place_weighted(
+    ship_lengths: &[usize],
+    setter: &mut dyn ShipSetter,
+    rng: &mut dyn RngCore,
+    weight: &dyn Fn(&ShipPlacement, &[ShipPlacement]) -> f32,
+) -> Result<(), PlacementError> {
+    let mut ships = ship_lengths.to_vec();
+    ships.sort_unstable_by(|a, b| b.cmp(a));
+
+    for _ in 0..MAX_WEIGHTED_ATTEMPTS {
+        let mut placed: Vec<ShipPlacement> = Vec::with_capacity(ships.len());
+        for ship in ships.iter().copied() {
+            let candidates = ship_placements(&*setter, ship)
+                .map(|(ix, d)| ShipPlacement::new(ix, ship, d))
+                .collect::<Vec<_>>();
+            let weights = candidates.iter().map(|c| weight(c, &placed)).collect::<Vec<_>>();
+            match pick_weighted(&weights, rng) {
+                Some(i) => {
+                    let p = candidates[i];
+                    setter.try_place_ship(p.start, p.ship_length, p.direction)?;
+                    placed.push(p);
+                }
+                None => break,
+            }
+        }
+
+        if placed.len() == ships.len() {
+            return Ok(());
+        }
+
+        for p in placed {
+            setter.remove_ship(p.start, p.ship_length, p.direction)?;
+        }
+    }
+
+    backtracking_placer(ship_lengths, setter, rng)
+}
+
+fn pick_weighted(weights: &[f32], rng: &mut dyn RngCore) -> Option<usize> {
+    let total = weights.iter().map(|w| w.max(0.0)).sum::<f32>();
+    if weights.is_empty() || total <= 0.0 {
+        // Note: Without positive weights, every position is equally likely
+        return match weights.len() {
+            0 => None,
+            len => Some(rng.gen_range(0..len as u32) as usize),
+        };
+    }
+
+    let mut remaining = rng.gen_range(0.0..total);
+    for (i, w) in weights.iter().enumerate() {
+        remaining -= w.max(0.0);
+        if remaining < 0.0 {
+            return Some(i);
+        }
+    }
+
+    // Note: Rounding errors can leave a tiny rest, the last positive weight takes it
+    weights.iter().rposition(|w| *w > 0.0)
+}
+
+fn distance_to_edge(ix: BoardIndex, side_length: usize) -> usize {
+    [ix.row(), ix.column(), side_length - 1 - ix.row(), side_length - 1 - ix.column()]
+        .into_iter()
+        .min()
+        .unwrap()
+}
+
+/// Smallest number of king moves between a square of `a` and a square of any ship in `others`
+fn distance_to_ships(a: &ShipPlacement, others: &[ShipPlacement]) -> Option<usize> {
+    others
+        .iter()
+        .flat_map(|o| o.squares())
+        .flat_map(|b| a.squares().map(move |s| s.chebyshev_distance(b)))
+        .min()
+}
+
+/// What a [`WeightedPlacer`] prefers when choosing the positions of ships
+#[derive(Debug, Clone)]
+pub enum Preference {
+    /// Positions with many squares on the edge of the board
+    Edges,
+    /// Positions far away from the edges of the board
+    AwayFromEdges,
+    /// Positions far away from ships that have already been placed
+    Spread,
+    /// Positions close to ships that have already been placed
+    Cluster,
+    /// Positions away from the squares an opponent tends to shoot at. The heat map holds
+    /// how often (or how likely) the opponent shoots at each square.
+    AwayFromHeat(HeatMap),
+}
+
+impl Preference {
+    /// Avoids the squares an opponent shot at, counting how often each square was hit
+    /// (e.g. the shots of previous games)
+    pub fn away_from_shots(
+        side_length: usize,
+        shots: impl IntoIterator<Item = BoardIndex>,
+    ) -> Preference {
+        let mut heat_map = HeatMap::new_initialized_with_side_length(0.0, side_length);
+        for ix in shots {
+            if heat_map.contains(ix) {
+                heat_map[ix] += 1.0;
+            }
+        }
+
+        Preference::AwayFromHeat(heat_map)
+    }
+}
+
+/// Places ships randomly, but prefers positions according to its [`Preference`].
+///
+/// `strength` controls how strong the preference is (0 places ships uniformly at random).
+pub struct WeightedPlacer {
+    pub preference: Preference,
+    pub strength: f32,
+    rng: ChaCha8Rng,
+}
+
+impl WeightedPlacer {
+    pub fn new(preference: Preference, strength: f32, seed: u64) -> WeightedPlacer {
+        WeightedPlacer { preference, strength, rng: placement_rng(seed) }
+    }
+}
+
+impl PlacementStrategy for WeightedPlacer {
+    fn place(
+        &mut self,
+        ship_lengths: &[usize],
+        setter: &mut dyn ShipSetter,
+    ) -> Result<(), PlacementError> {
+        let side_length = setter.side_length();
+        let (preference, strength) = (&self.preference, self.strength);
+        let max_heat = match preference {
+            Preference::AwayFromHeat(heat_map) => heat_map.iter().fold(0.0f32, f32::max),
+            _ => 0.0,
+        };
+        let heat =
+            |heat_map: &HeatMap, ix: BoardIndex| match heat_map.contains(ix) && max_heat > 0.0 {
+                true => heat_map[ix] / max_heat,
+                false => 0.0,
+            };
+
+        place_weighted(ship_lengths, setter, &mut self.rng, &|p, placed| match preference {
+            Preference::Edges => {
+                let edge_squares =
+                    p.squares().filter(|ix| distance_to_edge(*ix, side_length) == 0).count();
+                (1.0 + edge_squares as f32).powf(strength)
+            }
+            Preference::AwayFromEdges => {
+                let distance =
+                    p.squares().map(|ix| distance_to_edge(ix, side_length)).min().unwrap_or(0);
+                (1.0 + distance as f32).powf(strength)
+            }
+            // Note: Ships never overlap, so the distance to other ships is at least 1. Zero
+            //       weights (spread) and divisions by zero (cluster) cannot happen.
+            Preference::Spread => match distance_to_ships(p, placed) {
+                Some(distance) => (distance as f32).powf(strength),
+                None => 1.0,
+            },
+            Preference::Cluster => match distance_to_ships(p, placed) {
+                Some(distance) => (1.0 / distance as f32).powf(strength),
+                None => 1.0,
+            },
+            Preference::AwayFromHeat(heat_map) => {
+                // Note: The average heat is between 0 and 1. Cold positions get weights close to 1.
+                let average =
+                    p.squares().map(|ix| heat(heat_map, ix)).sum::<f32>() / p.ship_length as f32;
+                (1.01 - average).powf(strength)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{
+        random_placer, validate_layout, BattleshipBoardContent, BoardFiller, SquareContent,
+        CLASSIC_FLEET,
+    };
+
+    use super::*;
+
+    fn fill(strategy: &mut dyn PlacementStrategy) -> BattleshipBoardContent {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.fill_with_strategy(CLASSIC_FLEET, strategy).unwrap();
+        board
+    }
+
+    fn average(preference: Preference, measure: &dyn Fn(&[ShipPlacement]) -> f32) -> f32 {
+        (0..20)
+            .map(|seed| {
+                let mut placer = WeightedPlacer::new(preference.clone(), 4.0, seed);
+                measure(&validate_layout(&fill(&mut placer), CLASSIC_FLEET).unwrap())
+            })
+            .sum::<f32>()
+            / 20.0
+    }
+
+    fn edge_squares(ships: &[ShipPlacement]) -> f32 {
+        ships.iter().flat_map(|s| s.squares()).filter(|ix| distance_to_edge(*ix, 10) == 0).count()
+            as f32
+    }
+
+    fn average_distance(ships: &[ShipPlacement]) -> f32 {
+        let distances = ships.iter().map(|s| {
+            distance_to_ships(s, &ships.iter().copied().filter(|o| o != s).collect::<Vec<_>>())
+                .unwrap()
+        });
+        distances.sum::<usize>() as f32 / ships.len() as f32
+    }
+
+    #[rstest]
+    #[case(&mut SeededPlacer::new(random_placer, 1))]
+    #[case(&mut WeightedPlacer::new(Preference::Edges, 2.0, 1))]
+    #[case(&mut WeightedPlacer::new(Preference::AwayFromEdges, 2.0, 1))]
+    #[case(&mut WeightedPlacer::new(Preference::Spread, 2.0, 1))]
+    #[case(&mut WeightedPlacer::new(Preference::Cluster, 2.0, 1))]
+    #[case(&mut WeightedPlacer::new(Preference::away_from_shots(10, []), 2.0, 1))]
+    fn places_valid_fleet(#[case] strategy: &mut dyn PlacementStrategy) {
+        assert!(validate_layout(&fill(strategy), CLASSIC_FLEET).is_ok());
+    }
+
+    #[test]
+    fn reproducible() {
+        assert_eq!(
+            fill(&mut WeightedPlacer::new(Preference::Spread, 2.0, 42)),
+            fill(&mut WeightedPlacer::new(Preference::Spread, 2.0, 42))
+        );
+        assert_eq!(
+            fill(&mut SeededPlacer::new(random_placer, 42)),
+            fill(&mut SeededPlacer::new(random_placer, 42))
+        );
+    }
+
+    #[test]
+    fn edges() {
+        let favoring = average(Preference::Edges, &edge_squares);
+        let avoiding = average(Preference::AwayFromEdges, &edge_squares);
+        assert!(favoring > 12.0, "{}", favoring);
+        assert!(avoiding < 3.0, "{}", avoiding);
+    }
+
+    #[test]
+    fn spread_and_cluster() {
+        let spread = average(Preference::Spread, &average_distance);
+        let cluster = average(Preference::Cluster, &average_distance);
+        assert!(spread > cluster + 0.5, "{} {}", spread, cluster);
+    }
+
+    #[test]
+    fn avoids_heat() {
+        // Note: The opponent always shoots at the left half of the board
+        let shots = (0..100).map(|ix| BoardIndex::from_index(ix, 10)).filter(|ix| ix.column() < 5);
+        let mut placer = WeightedPlacer::new(Preference::away_from_shots(10, shots), 4.0, 42);
+        let board = fill(&mut placer);
+        let left = (0..100)
+            .map(|ix| BoardIndex::from_index(ix, 10))
+            .filter(|ix| ix.column() < 5 && board[*ix].is_ship());
+        assert!(left.count() <= 2);
+    }
+
+    #[test]
+    fn fallback_on_dead_end() {
+        // Note: A ship in the middle row leaves no room for the second ship
+        let mut board =
+            BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 3);
+        let middle = |p: &ShipPlacement, _: &[ShipPlacement]| {
+            if p.start == BoardIndex::from("A2") {
+                1.0
+            } else {
+                0.0
+            }
+        };
+        place_weighted(&[3, 3], &mut board, &mut placement_rng(42), &middle).unwrap();
+        assert!(validate_layout(&board, &[3, 3]).is_ok());
+    }
+
+    #[test]
+    fn pick_weighted_respects_weights() {
+        let mut rng = placement_rng(42);
+        assert_eq!(None, pick_weighted(&[], &mut rng));
+        assert!((0..50).all(|_| pick_weighted(&[0.0, 1.0, 0.0], &mut rng) == Some(1)));
+        assert!((0..50).all(|_| pick_weighted(&[0.0, 0.0], &mut rng).is_some()));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{
+    AdjacencyRule, BattleshipBoardContent, BoardDimensions, BoardIndex, Direction, GameRules, GenericBoardContent,
+    RuleAwareSetter, ShipFinder, ShipFindingResult, ShipSetter, ShotStrategy, SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Using generic types with other type arguments (`GenericBoardContent<f32>`)
+    * Floating point numbers
+    * Reusing existing trait implementations for new purposes
+
+    Recommended readings for this module:
+
+    * Floating-point types: https://doc.rust-lang.org/book/ch03-02-data-types.html#floating-point-types
+    * `f32::total_cmp`: https://doc.rust-lang.org/std/primitive.f32.html#method.total_cmp
+*/
+
+pub type HeatMap = GenericBoardContent<f32>;
+
+/// Shoots at the square that is most likely covered by one of the remaining ships.
+///
+/// For every ship that has not been sunk yet, all placements that are still possible
+/// on the shooting board are enumerated. If there are hits of ships that have not been
+/// sunk, only placements covering these hits are taken into account.
+///
+/// This is an approximation: every ship is placed on its own, ignoring the other remaining
+/// ships. Enumerating complete fleet layouts would be exact, but there are billions of them
+/// on a classic board (see [`crate::count_fleet_layouts`]).
+#[derive(Debug, Clone)]
+pub struct ProbabilityDensityStrategy {
+    ship_lengths: Vec<usize>,
+    adjacency: AdjacencyRule,
+}
+
+impl ProbabilityDensityStrategy {
+    pub fn new(rules: &GameRules) -> ProbabilityDensityStrategy {
+        ProbabilityDensityStrategy { ship_lengths: rules.ship_lengths.clone(), adjacency: rules.adjacency }
+    }
+
+    /// Calculates the heat map and picks the square to shoot at
+    pub fn evaluate(&self, shooting_board: &BattleshipBoardContent) -> (HeatMap, Option<BoardIndex>) {
+        let heat_map = self.heat_map(shooting_board);
+        let side_length = shooting_board.side_length();
+
+        // Note: `f32` does not implement `Ord` because of NaN. Therefore, we use `total_cmp`.
+        //       `max_by` returns the last maximum, so we iterate in reverse to prefer the first one.
+        let shot = (0..shooting_board.size())
+            .rev()
+            .map(|ix| BoardIndex::from_index(ix, side_length))
+            .filter(|ix| shooting_board[*ix] == SquareContent::Unknown)
+            .max_by(|a, b| heat_map[*a].total_cmp(&heat_map[*b]));
+
+        (heat_map, shot)
+    }
+
+    pub fn heat_map(&self, shooting_board: &BattleshipBoardContent) -> HeatMap {
+        let remaining = self.remaining_ships(shooting_board);
+        let targeting = shooting_board.iter().any(|s| s == SquareContent::HitShip);
+
+        let heat_map = self.accumulate(shooting_board, &remaining, targeting);
+        if targeting && heat_map.iter().all(|h| h == 0.0) {
+            // No placement explains the hits (e.g. touching ships) -> fall back to hunting
+            return self.accumulate(shooting_board, &remaining, false);
+        }
+
+        heat_map
+    }
+
+    fn remaining_ships(&self, shooting_board: &BattleshipBoardContent) -> Vec<usize> {
+        let side_length = shooting_board.side_length();
+        let mut remaining = self.ship_lengths.clone();
+        let mut visited = GenericBoardContent::<bool>::new_with_side_length(side_length);
+        for ix in (0..shooting_board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+            if shooting_board[ix] != SquareContent::SunkenShip || visited[ix] {
+                continue;
+            }
+
+            if let ShipFindingResult::CompleteShip(r) | ShipFindingResult::PartialShip(r) = shooting_board.try_find_ship(ix) {
+                let length = r.length();
+                r.into_iter().for_each(|i| visited[i] = true);
+                if let Some(pos) = remaining.iter().position(|l| *l == length) {
+                    remaining.swap_remove(pos);
+                }
+            }
+        }
+
+        remaining
+    }
+
+    // Note: Builds a board on which every square that could contain a ship is
+    //       `Water` (=free). This allows us to use `can_place_ship` for the checks.
+    fn free_squares(&self, shooting_board: &BattleshipBoardContent) -> BattleshipBoardContent {
+        let side_length = shooting_board.side_length();
+        let mut free = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Ship, side_length);
+        for ix in (0..shooting_board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+            match shooting_board[ix] {
+                SquareContent::Unknown | SquareContent::HitShip => free[ix] = SquareContent::Water,
+                _ => (),
+            }
+        }
+
+        if self.adjacency == AdjacencyRule::NoTouching {
+            // Squares around sunken ships cannot contain other ships
+            for ix in (0..shooting_board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+                if shooting_board[ix] != SquareContent::SunkenShip {
+                    continue;
+                }
+
+                for neighbor in std::iter::once(ix).chain(ix.neighbors8(side_length)) {
+                    free[neighbor] = SquareContent::Ship;
+                }
+            }
+        }
+
+        free
+    }
+
+    fn accumulate(&self, shooting_board: &BattleshipBoardContent, ships: &[usize], targeting: bool) -> HeatMap {
+        let side_length = shooting_board.side_length();
+        let mut free = self.free_squares(shooting_board);
+        let setter = RuleAwareSetter::new(&mut free, AdjacencyRule::MayTouch);
+
+        let mut heat_map = HeatMap::new_initialized_with_side_length(0.0, side_length);
+        for ship_length in ships.iter().copied() {
+            // A ship with length one looks the same in both directions -> count it only once
+            let directions: &[Direction] = match ship_length {
+                1 => &[Direction::Horizontal],
+                _ => &[Direction::Horizontal, Direction::Vertical],
+            };
+
+            let mut counts = HeatMap::new_initialized_with_side_length(0.0, side_length);
+            let mut total = 0.0;
+            for direction in directions.iter().copied() {
+                for ix in (0..shooting_board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+                    if !matches!(setter.can_place_ship(ix, ship_length, direction), Ok(true)) {
+                        continue;
+                    }
+
+                    let squares = std::iter::successors(Some(ix), |i| i.try_next(direction, side_length))
+                        .take(ship_length)
+                        .collect::<Vec<_>>();
+                    let weight = match targeting {
+                        true => squares.iter().filter(|i| shooting_board[**i] == SquareContent::HitShip).count() as f32,
+                        false => 1.0,
+                    };
+                    if weight == 0.0 {
+                        continue;
+                    }
+
+                    total += weight;
+                    squares.iter().for_each(|i| counts[*i] += weight);
+                }
+            }
+
+            if total > 0.0 {
+                for ix in (0..shooting_board.size()).map(|ix| BoardIndex::from_index(ix, side_length)) {
+                    if shooting_board[ix] == SquareContent::Unknown {
+                        heat_map[ix] += counts[ix] / total;
+                    }
+                }
+            }
+        }
+
+        heat_map
+    }
+}
+
+impl Default for ProbabilityDensityStrategy {
+    fn default() -> Self {
+        Self::new(&GameRules::default())
+    }
+}
+
+impl ShotStrategy for ProbabilityDensityStrategy {
+    fn next_shot(&mut self, shooting_board: &BattleshipBoardContent) -> Option<BoardIndex> {
+        self.evaluate(shooting_board).1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{auto_play, GameState, SinglePlayerGame};
+
+    use super::*;
+
+    #[test]
+    fn empty_board_prefers_center() {
+        let board = BattleshipBoardContent::new();
+        let (heat_map, shot) = ProbabilityDensityStrategy::default().evaluate(&board);
+        assert!(heat_map[BoardIndex::from("E5")] > heat_map[BoardIndex::from("A1")]);
+
+        let shot = shot.unwrap();
+        assert!((3..=6).contains(&shot.column()) && (3..=6).contains(&shot.row()));
+    }
+
+    #[test]
+    fn known_squares_are_cold() {
+        let mut board = BattleshipBoardContent::new();
+        board[BoardIndex::from("E5")] = SquareContent::Water;
+        board[BoardIndex::from("F5")] = SquareContent::HitShip;
+
+        let heat_map = ProbabilityDensityStrategy::default().heat_map(&board);
+        assert_eq!(0.0, heat_map[BoardIndex::from("E5")]);
+        assert_eq!(0.0, heat_map[BoardIndex::from("F5")]);
+    }
+
+    #[test]
+    fn target_hits() {
+        let mut board = BattleshipBoardContent::new();
+        board[BoardIndex::from("E5")] = SquareContent::HitShip;
+        board[BoardIndex::from("F5")] = SquareContent::HitShip;
+        board[BoardIndex::from("G5")] = SquareContent::Water;
+
+        let (heat_map, shot) = ProbabilityDensityStrategy::default().evaluate(&board);
+        assert_eq!(Some(BoardIndex::from("D5")), shot);
+        assert_eq!(0.0, heat_map[BoardIndex::from("A1")]);
+    }
+
+    #[test]
+    fn sunken_ships_are_removed() {
+        let rules = GameRules { ship_lengths: vec![2, 2, 3], ..Default::default() };
+        let mut board = BattleshipBoardContent::new();
+        board[BoardIndex::from("A1")] = SquareContent::SunkenShip;
+        board[BoardIndex::from("B1")] = SquareContent::SunkenShip;
+        board[BoardIndex::from("C1")] = SquareContent::Water;
+        board[BoardIndex::from("A2")] = SquareContent::Water;
+        board[BoardIndex::from("B2")] = SquareContent::Water;
+
+        assert_eq!(vec![3, 2], ProbabilityDensityStrategy::new(&rules).remaining_ships(&board));
+    }
+
+    #[test]
+    fn no_touching_around_sunken_ships() {
+        let mut board = BattleshipBoardContent::new();
+        board[BoardIndex::from("E5")] = SquareContent::SunkenShip;
+        board[BoardIndex::from("E6")] = SquareContent::SunkenShip;
+
+        let heat_map = ProbabilityDensityStrategy::default().heat_map(&board);
+        assert_eq!(0.0, heat_map[BoardIndex::from("D5")]);
+        assert_eq!(0.0, heat_map[BoardIndex::from("F7")]);
+
+        let rules = GameRules { adjacency: AdjacencyRule::MayTouch, ..Default::default() };
+        let heat_map = ProbabilityDensityStrategy::new(&rules).heat_map(&board);
+        assert!(heat_map[BoardIndex::from("D5")] > 0.0);
+    }
+
+    #[test]
+    fn heat_map_with_side_length() {
+        let rules = GameRules { side_length: 8, ..Default::default() };
+        let board = BattleshipBoardContent::new_with_side_length(8);
+        let heat_map = ProbabilityDensityStrategy::new(&rules).heat_map(&board);
+        assert_eq!(8, heat_map.side_length());
+        assert!(heat_map.iter().all(|h| h > 0.0));
+    }
+
+    #[test]
+    fn auto_play_sinks_all_ships() {
+        let mut game = SinglePlayerGame::new();
+        assert_eq!(GameState::AllShipsSunken, auto_play(&mut game, &mut ProbabilityDensityStrategy::default()));
+        assert!(game.log().count() <= 100);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{BoardIndex, GenericBoardContent};
+
+/*
+    Learnings in this module:
+
+    * Views on two-dimensional parts of a board
+    * Clamping user input instead of panicking
+
+    Recommended readings for this module:
+
+    * `Ord::clamp`: https://doc.rust-lang.org/std/cmp/trait.Ord.html#method.clamp
+    * Iterator adapters: https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.flat_map
+*/
+
+/// Rectangular part of a board from `first` (top left) to `last` (bottom right)
+pub struct Region<'a, T> {
+    board: &'a GenericBoardContent<T>,
+    first: BoardIndex,
+    last: BoardIndex,
+}
+
+impl<'a, T: Default + Copy> Region<'a, T> {
+    // Note: Crate-private, use `GenericBoardContent::region` which makes sure that the corners are valid
+    pub(crate) fn new(board: &'a GenericBoardContent<T>, first: BoardIndex, last: BoardIndex) -> Self {
+        debug_assert!(first.column() <= last.column() && first.row() <= last.row());
+        Region { board, first, last }
+    }
+
+    pub fn first(&self) -> BoardIndex {
+        self.first
+    }
+
+    pub fn last(&self) -> BoardIndex {
+        self.last
+    }
+
+    pub fn width(&self) -> usize {
+        self.last.column() - self.first.column() + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.last.row() - self.first.row() + 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, ix: BoardIndex) -> bool {
+        (self.first.column()..=self.last.column()).contains(&ix.column())
+            && (self.first.row()..=self.last.row()).contains(&ix.row())
+    }
+
+    /// Squares of the region in board order
+    pub fn squares(&self) -> impl Iterator<Item = BoardIndex> {
+        let (first, last) = (self.first, self.last);
+        (first.row()..=last.row())
+            .flat_map(move |r| (first.column()..=last.column()).map(move |c| BoardIndex::from_col_row(c, r)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let board = self.board;
+        self.squares().map(move |ix| board[ix])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{BattleshipBoardContent, SquareContent};
+
+    use super::*;
+
+    #[rstest]
+    #[case("B2", "C4", "B2", "C4")]
+    #[case("C4", "B2", "B2", "C4")] // corners in any order
+    #[case("C2", "B4", "B2", "C4")]
+    #[case("G7", "Z26", "G7", "H8")] // clamped to the board
+    fn region_corners(#[case] a: &str, #[case] b: &str, #[case] first: &str, #[case] last: &str) {
+        let board = BattleshipBoardContent::new_with_side_length(8);
+        let region = board.region(a.into(), b.into());
+        assert_eq!(BoardIndex::from(first), region.first());
+        assert_eq!(BoardIndex::from(last), region.last());
+    }
+
+    #[test]
+    fn region_squares() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board[BoardIndex::from("C3")] = SquareContent::Ship;
+
+        let region = board.region("B2".into(), "D3".into());
+        assert_eq!((3, 2, 6), (region.width(), region.height(), region.len()));
+        assert_eq!(
+            ["B2", "C2", "D2", "B3", "C3", "D3"].into_iter().map(BoardIndex::from).collect::<Vec<_>>(),
+            region.squares().collect::<Vec<_>>()
+        );
+        assert_eq!(1, region.iter().filter(|c| *c == SquareContent::Ship).count());
+        assert!(region.contains("C3".into()));
+        assert!(!region.contains("C4".into()));
+        assert!(!region.contains("A2".into()));
+    }
+
+    #[rstest]
+    #[case("E5", 1, "D4", "F6")]
+    #[case("A1", 1, "A1", "B2")]
+    #[case("J10", 2, "H8", "J10")]
+    #[case("E5", 20, "A1", "J10")]
+    #[case("E5", usize::MAX, "A1", "J10")]
+    fn region_around(#[case] ix: &str, #[case] radius: usize, #[case] first: &str, #[case] last: &str) {
+        let board = BattleshipBoardContent::new();
+        let region = board.region_around(ix.into(), radius);
+        assert_eq!(BoardIndex::from(first), region.first());
+        assert_eq!(BoardIndex::from(last), region.last());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{BattleshipBoardContent, BoardIndex, Fleet, GameRules, Shot, SinglePlayerGame};
+
+/*
+    Learnings in this module:
+
+    * Implementing the `Iterator` trait for a custom type
+    * Adding methods to a type in a different module with another `impl` block
+    * Accepting any iterable with `impl IntoIterator`
+
+    Recommended readings for this module:
+
+    * `Iterator` trait: https://doc.rust-lang.org/std/iter/trait.Iterator.html
+    * Implementing iterators: https://doc.rust-lang.org/book/ch13-02-iterators.html#creating-our-own-iterators-with-the-iterator-trait
+    * `IntoIterator` trait: https://doc.rust-lang.org/std/iter/trait.IntoIterator.html
+*/
+
+impl SinglePlayerGame {
+    /// Rebuilds a game by firing the given shots at the board.
+    ///
+    /// Panics if the side length of the board does not match the rules.
+    pub fn replay(board: BattleshipBoardContent, rules: GameRules, shots: impl IntoIterator<Item = BoardIndex>) -> SinglePlayerGame {
+        let mut game = SinglePlayerGame::with_board(board, rules);
+        for ix in shots {
+            game.shoot(ix);
+        }
+
+        game
+    }
+
+    /// Shooting boards after each shot of the log
+    pub fn shooting_board_states(&self) -> ShootingBoardStates<'_> {
+        ShootingBoardStates {
+            game: SinglePlayerGame::with_fleet(Fleet::with_shapes(self.fleet().shapes()), self.rules().clone()),
+            shots: self.shots().iter(),
+        }
+    }
+}
+
+pub struct ShootingBoardStates<'a> {
+    game: SinglePlayerGame,
+    shots: std::slice::Iter<'a, Shot>,
+}
+
+impl<'a> Iterator for ShootingBoardStates<'a> {
+    type Item = BattleshipBoardContent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let shot = self.shots.next()?;
+        self.game.shoot(shot.location);
+        Some(self.game.shooting_board().clone())
+    }
+
+    // Note: Knowing the exact size allows e.g. `collect` to allocate the right amount of memory
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.shots.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for ShootingBoardStates<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Direction, GameState, ShipSetter, SquareContent};
+
+    use super::*;
+
+    fn get_board() -> BattleshipBoardContent {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("A1".into(), 2, Direction::Horizontal).unwrap();
+        board
+    }
+
+    fn get_rules() -> GameRules {
+        GameRules { ship_lengths: vec![2], ..Default::default() }
+    }
+
+    #[test]
+    fn replay() {
+        let game = SinglePlayerGame::replay(get_board(), get_rules(), ["C3".into(), "A1".into(), "B1".into()]);
+        assert_eq!(3, game.log().count());
+        assert_eq!(GameState::AllShipsSunken, game.game_state());
+    }
+
+    #[test]
+    #[should_panic(expected = "Side length of the board does not match the rules")]
+    fn replay_with_wrong_side_length() {
+        let rules = GameRules { side_length: 8, ..get_rules() };
+        SinglePlayerGame::replay(get_board(), rules, ["A1".into()]);
+    }
+
+    #[test]
+    fn replay_log_of_other_game() {
+        let mut game = SinglePlayerGame::with_seed(GameRules::default(), 42);
+        for ix in ["A1", "B2", "E5", "J10", "C7"] {
+            game.shoot(ix.into());
+        }
+
+        let replayed = SinglePlayerGame::replay(game.fleet().to_board(10), game.rules().clone(), game.log().map(|s| s.location));
+        assert_eq!(game.shooting_board(), replayed.shooting_board());
+    }
+
+    #[test]
+    fn shooting_board_states() {
+        let game = SinglePlayerGame::replay(get_board(), get_rules(), ["C3".into(), "A1".into(), "B1".into()]);
+        let states = game.shooting_board_states();
+        assert_eq!(3, states.len());
+
+        let states = states.collect::<Vec<_>>();
+        assert_eq!(SquareContent::Water, states[0][BoardIndex::from("C3")]);
+        assert_eq!(SquareContent::Unknown, states[0][BoardIndex::from("A1")]);
+        assert_eq!(SquareContent::HitShip, states[1][BoardIndex::from("A1")]);
+        assert_eq!(SquareContent::SunkenShip, states[2][BoardIndex::from("A1")]);
+        assert_eq!(game.shooting_board(), &states[2]);
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Index;
 
-use crate::{BOARD_SIDE_LENGTH, GenericBoardContent};
+use crate::{BoardDimensions, GenericBoardContent};
 
 /*
     Learnings in this module:
@@ -29,11 +29,20 @@ impl<'a, T: Default + Copy> Row<'a, T> {
     }
 
     pub fn as_slice(&self) -> &[T] {
-        &self.board.as_slice()[(self.row_index * BOARD_SIDE_LENGTH)..((self.row_index + 1) * BOARD_SIDE_LENGTH)]
+        let side_length = self.board.side_length();
+        &self.board.as_slice()[(self.row_index * side_length)..((self.row_index + 1) * side_length)]
+    }
+
+    pub fn len(&self) -> usize {
+        self.board.side_length()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn has_next(&self) -> bool {
-        self.row_index < (BOARD_SIDE_LENGTH - 1)
+        self.row_index < (self.board.side_length() - 1)
     }
 }
 
@@ -41,11 +50,12 @@ impl<'a, T> Index<usize> for Row<'a, T> {
     type Output = T;
 
     fn index(&self, ix: usize) -> &Self::Output {
-        if ix >= BOARD_SIDE_LENGTH {
+        let side_length = self.board.side_length();
+        if ix >= side_length {
             panic!("Index out of bounds");
         }
 
-        &self.board[self.row_index * BOARD_SIDE_LENGTH + ix]
+        &self.board[self.row_index * side_length + ix]
     }
 }
 
@@ -64,7 +74,7 @@ impl<'a, T> Iterator for RowsIterator<'a, T> {
     type Item = Row<'a, T>;
 
     fn next(&mut self) -> Option<Row<'a, T>> {
-        if self.next_row >= BOARD_SIDE_LENGTH {
+        if self.next_row >= self.board.side_length() {
             return None;
         }
 
@@ -76,9 +86,7 @@ impl<'a, T> Iterator for RowsIterator<'a, T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{BattleshipBoardContent, SquareContent};
-
-    use super::*;
+    use crate::{BattleshipBoardContent, SquareContent, BOARD_SIDE_LENGTH};
 
     #[test]
     fn slice_row() {
@@ -104,4 +112,15 @@ mod tests {
         assert_eq!(BOARD_SIDE_LENGTH, b.rows().count());
         assert_eq!(b.rows().nth(1).unwrap().as_slice()[0], square_content);
     }
+
+    #[test]
+    fn row_iterator_with_side_length() {
+        let mut b = BattleshipBoardContent::new_with_side_length(8);
+        b[8 * 7 + 7] = SquareContent::Ship;
+        assert_eq!(8, b.rows().count());
+        let last = b.rows().last().unwrap();
+        assert_eq!(8, last.len());
+        assert!(!last.has_next());
+        assert_eq!(SquareContent::Ship, last[7]);
+    }
 }
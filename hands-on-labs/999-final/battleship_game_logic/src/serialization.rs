@@ -0,0 +1,364 @@
+use std::{convert::TryFrom, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    BattleshipBoardContent, BoardDimensions, Direction, Fleet, GameRules, GenericBoardContent, InvalidBoardSize, ShapePlacement,
+    ShipPlacement, Shot, SinglePlayerGame, SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Serialization with serde
+    * Optional cargo features and conditional compilation (`#[cfg(feature = ...)]`)
+    * Proxy types (`#[serde(into = ..., try_from = ...)]`) to separate the stored
+      format from the in-memory representation
+
+    Recommended readings for this module:
+
+    * serde: https://serde.rs/
+    * Container attributes: https://serde.rs/container-attrs.html
+    * Cargo features: https://doc.rust-lang.org/cargo/reference/features.html
+    * Conditional compilation: https://doc.rust-lang.org/reference/conditional-compilation.html
+*/
+
+/// Version of the format in which games are saved.
+///
+/// Version 1 stores a game as JSON object with the fields `version`, `rules`,
+/// `seed`, `board`, `fleet`, `shooting_board`, `log` and `volleys`. Boards are stored as
+/// `{ "side_length": n, "squares": [...] }` with n*n squares in row order.
+/// `fleet` contains the positions of the ships, either as `{ "start", "ship_length", "direction" }`
+/// for straight ships or as `{ "origin", "shape" }` for other shapes. `volleys` contains the
+/// positions in the log at which the volleys start. Both are optional, as are the rules
+/// `repeat_shots`, `firing_mode` and `reveal_water`.
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SavedBoard<T> {
+    side_length: usize,
+    squares: Vec<T>,
+}
+
+impl<T> From<GenericBoardContent<T>> for SavedBoard<T> {
+    fn from(board: GenericBoardContent<T>) -> Self {
+        SavedBoard { side_length: board.side_length(), squares: board.into_iter().collect() }
+    }
+}
+
+impl<T> TryFrom<SavedBoard<T>> for GenericBoardContent<T> {
+    type Error = InvalidBoardSize;
+
+    fn try_from(saved: SavedBoard<T>) -> Result<Self, Self::Error> {
+        GenericBoardContent::from_squares(saved.side_length, saved.squares)
+    }
+}
+
+// Note: Straight ships are stored as `ShipPlacement`, other shapes as `ShapePlacement`.
+//       `untagged` lets serde pick the variant that matches the stored fields.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum SavedShip {
+    Straight(ShipPlacement),
+    Shaped(ShapePlacement),
+}
+
+impl SavedShip {
+    // Note: Stored ships are not trusted. Converting an empty ship or placing a ship
+    //       outside of the board would panic.
+    fn fits_on_board(&self, side_length: usize) -> bool {
+        let (origin, width, height) = match self {
+            SavedShip::Straight(p) if p.direction == Direction::Horizontal => (p.start, p.ship_length, 1),
+            SavedShip::Straight(p) => (p.start, 1, p.ship_length),
+            SavedShip::Shaped(p) => (p.origin, p.shape.width(), p.shape.height()),
+        };
+        width > 0
+            && height > 0
+            && origin.column().saturating_add(width) <= side_length
+            && origin.row().saturating_add(height) <= side_length
+    }
+}
+
+impl From<SavedShip> for ShapePlacement {
+    fn from(ship: SavedShip) -> Self {
+        match ship {
+            SavedShip::Straight(p) => p.into(),
+            SavedShip::Shaped(p) => p,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SavedGame {
+    version: u32,
+    rules: GameRules,
+    seed: Option<u64>,
+    board: BattleshipBoardContent,
+    // Note: Optional. Without a fleet, the ships are identified on the board.
+    #[serde(default)]
+    fleet: Vec<SavedShip>,
+    shooting_board: BattleshipBoardContent,
+    log: Vec<Shot>,
+    // Note: Optional. Without volleys, every shot is a volley of its own.
+    #[serde(default)]
+    volleys: Vec<usize>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    UnsupportedVersion(u32),
+    /// Boards do not have the side length defined in the rules
+    SideLengthMismatch,
+    /// The board does not contain the fleet defined in the rules
+    InvalidFleet,
+    /// Shot with the given position in the log does not match the board
+    LogMismatch(usize),
+    /// The shooting board does not match the log
+    ShootingBoardMismatch,
+    /// Volleys do not start at ascending positions of the log
+    InvalidVolleys,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::UnsupportedVersion(v) => write!(f, "Unsupported save format version {}", v),
+            LoadError::SideLengthMismatch => write!(f, "Side length of boards does not match rules"),
+            LoadError::InvalidFleet => write!(f, "Board does not contain the fleet defined in the rules"),
+            LoadError::LogMismatch(ix) => write!(f, "Shot #{} does not match the board", ix + 1),
+            LoadError::ShootingBoardMismatch => write!(f, "Shooting board does not match the shot log"),
+            LoadError::InvalidVolleys => write!(f, "Volleys do not match the shot log"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<SinglePlayerGame> for SavedGame {
+    fn from(game: SinglePlayerGame) -> Self {
+        SavedGame {
+            version: SAVE_FORMAT_VERSION,
+            rules: game.rules().clone(),
+            seed: game.seed(),
+            board: game.fleet_board().clone(),
+            fleet: game
+                .fleet()
+                .shapes()
+                .into_iter()
+                .map(|s| match s.to_ship_placement() {
+                    Some(p) => SavedShip::Straight(p),
+                    None => SavedShip::Shaped(s),
+                })
+                .collect(),
+            shooting_board: game.shooting_board().clone(),
+            log: game.log().copied().collect(),
+            volleys: game.volley_starts().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<SavedGame> for SinglePlayerGame {
+    type Error = LoadError;
+
+    fn try_from(saved: SavedGame) -> Result<Self, Self::Error> {
+        if saved.version != SAVE_FORMAT_VERSION {
+            return Err(LoadError::UnsupportedVersion(saved.version));
+        }
+
+        if saved.board.side_length() != saved.rules.side_length || saved.shooting_board.side_length() != saved.rules.side_length {
+            return Err(LoadError::SideLengthMismatch);
+        }
+
+        if saved.board.iter().any(|s| !matches!(s, SquareContent::Water | SquareContent::Ship))
+            || saved.board.iter().filter(|s| *s == SquareContent::Ship).count() != saved.rules.fleet_size()
+        {
+            return Err(LoadError::InvalidFleet);
+        }
+
+        if saved.fleet.iter().any(|s| !s.fits_on_board(saved.rules.side_length)) {
+            return Err(LoadError::InvalidFleet);
+        }
+
+        // Note: We do not trust the stored shooting board. Replaying the log
+        //       results in the same board if the saved game is consistent.
+        let fleet = match saved.fleet.is_empty() {
+            true => Fleet::from_board(&saved.board),
+            false => Fleet::with_shapes(saved.fleet.into_iter().map(ShapePlacement::from).collect()),
+        };
+        if fleet.to_board(saved.rules.side_length) != saved.board {
+            return Err(LoadError::InvalidFleet);
+        }
+
+        let mut game = SinglePlayerGame::with_fleet(fleet, saved.rules);
+        for (ix, shot) in saved.log.iter().enumerate() {
+            if !game.fleet_board().contains(shot.location) || game.shoot(shot.location) != shot.result {
+                return Err(LoadError::LogMismatch(ix));
+            }
+        }
+
+        if *game.shooting_board() != saved.shooting_board {
+            return Err(LoadError::ShootingBoardMismatch);
+        }
+
+        if !saved.volleys.is_empty() {
+            let ascending = saved.volleys.windows(2).all(|w| w[0] < w[1]);
+            if saved.volleys[0] != 0 || !ascending || *saved.volleys.last().unwrap() >= saved.log.len() {
+                return Err(LoadError::InvalidVolleys);
+            }
+
+            game.set_volley_starts(saved.volleys);
+        }
+
+        game.set_seed(saved.seed);
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::{BoardIndex, Direction, FiringMode, GameState, ShipShape};
+
+    use super::*;
+
+    fn get_game() -> SinglePlayerGame {
+        let mut game = SinglePlayerGame::with_seed(GameRules::default(), 42);
+        for ix in ["A1", "B2", "E5", "J10"] {
+            game.shoot(ix.into());
+        }
+
+        game
+    }
+
+    #[test]
+    fn save_and_load() {
+        let game = get_game();
+        let json = serde_json::to_string(&game).unwrap();
+        let loaded: SinglePlayerGame = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(game.fleet_board(), loaded.fleet_board());
+        assert_eq!(game.shooting_board(), loaded.shooting_board());
+        assert_eq!(Some(42), loaded.seed());
+        assert_eq!(4, loaded.log().count());
+        assert_eq!(GameState::InProgress, loaded.game_state());
+    }
+
+    #[test]
+    fn schema() {
+        let value = serde_json::to_value(get_game()).unwrap();
+        assert_eq!(json!(SAVE_FORMAT_VERSION), value["version"]);
+        assert_eq!(json!(10), value["board"]["side_length"]);
+        assert_eq!(100, value["board"]["squares"].as_array().unwrap().len());
+        assert_eq!(json!("NoTouching"), value["rules"]["adjacency"]);
+        assert_eq!(json!({ "row": 0, "column": 0 }), value["log"][0]["location"]);
+    }
+
+    fn load(value: Value) -> Result<SinglePlayerGame, String> {
+        serde_json::from_value::<SinglePlayerGame>(value).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        value["version"] = json!(99);
+        assert_eq!(Err(LoadError::UnsupportedVersion(99).to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn invalid_board_size() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        value["board"]["squares"].as_array_mut().unwrap().pop();
+        assert_eq!(Err(InvalidBoardSize.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn log_does_not_match_board() {
+        let game = get_game();
+        let hit = game.log().position(|s| s.result == SquareContent::Water).unwrap();
+        let mut value = serde_json::to_value(game).unwrap();
+        value["log"][hit]["result"] = json!("HitShip");
+        assert_eq!(Err(LoadError::LogMismatch(hit).to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn shooting_board_does_not_match_log() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        let ix = BoardIndex::from("C3").to_index(10);
+        value["shooting_board"]["squares"][ix] = json!("Water");
+        assert_eq!(Err(LoadError::ShootingBoardMismatch.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn invalid_fleet() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        value["rules"]["ship_lengths"] = json!([5, 4]);
+        assert_eq!(Err(LoadError::InvalidFleet.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn load_without_fleet() {
+        let game = get_game();
+        let mut value = serde_json::to_value(&game).unwrap();
+        value.as_object_mut().unwrap().remove("fleet");
+
+        // Note: Ships are identified in board order, so the order of the fleet may differ
+        let (mut expected, mut actual) = (game.fleet().placements(), load(value).unwrap().fleet().placements());
+        expected.sort_by_key(|p| p.start);
+        actual.sort_by_key(|p| p.start);
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn fleet_does_not_match_board() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        value["fleet"].as_array_mut().unwrap().pop();
+        assert_eq!(Err(LoadError::InvalidFleet.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn fleet_outside_of_board() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        value["fleet"][0]["start"]["row"] = json!(1000);
+        assert_eq!(Err(LoadError::InvalidFleet.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn fleet_with_empty_ship() {
+        let mut value = serde_json::to_value(get_game()).unwrap();
+        value["fleet"][0]["ship_length"] = json!(0);
+        assert_eq!(Err(LoadError::InvalidFleet.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn save_and_load_volleys() {
+        let rules = GameRules { firing_mode: FiringMode::Salvo, ..Default::default() };
+        let mut game = SinglePlayerGame::with_seed(rules, 42);
+        game.shoot_salvo(&["A1".into(), "B2".into(), "C3".into()]).unwrap();
+        game.shoot_salvo(&["D4".into()]).unwrap();
+
+        let loaded: SinglePlayerGame = serde_json::from_value(serde_json::to_value(&game).unwrap()).unwrap();
+        assert_eq!(vec![3, 1], loaded.volleys().map(|v| v.len()).collect::<Vec<_>>());
+
+        let mut value = serde_json::to_value(&game).unwrap();
+        value["volleys"] = json!([0, 4]);
+        assert_eq!(Err(LoadError::InvalidVolleys.to_string()), load(value).map(|_| ()));
+    }
+
+    #[test]
+    fn save_and_load_shaped_fleet() {
+        let shape = ShipShape::new([(0, 0), (0, 1), (1, 1)]).unwrap();
+        let fleet = Fleet::with_shapes(vec![
+            ShapePlacement::new("B2".into(), shape),
+            ShipPlacement::new("F6".into(), 2, Direction::Vertical).into(),
+        ]);
+        let game = SinglePlayerGame::with_fleet(fleet, GameRules { ship_lengths: vec![3, 2], ..Default::default() });
+
+        let value = serde_json::to_value(&game).unwrap();
+        assert_eq!(json!([[0, 0], [0, 1], [1, 1]]), value["fleet"][0]["shape"]);
+        assert_eq!(json!(2), value["fleet"][1]["ship_length"]);
+
+        let loaded = load(value).unwrap();
+        assert_eq!(game.fleet().shapes(), loaded.fleet().shapes());
+    }
+}
@@ -1,6 +1,6 @@
 use std::ops::Index;
 
-use crate::{BoardIndex, BoardIndexRangeInclusive, Direction, SquareContent};
+use crate::{BoardDimensions, BoardIndex, BoardIndexRangeInclusive, Direction, SquareContent};
 
 /*
     Learnings in this module:
@@ -24,13 +24,26 @@ pub enum ShipFindingResult {
     PartialShip(BoardIndexRangeInclusive),
 }
 
+/// Squares of a ship with an arbitrary shape (sorted by row and column)
+#[derive(Debug, PartialEq, Eq)]
+pub enum ShapeFindingResult {
+    NoShip,
+    CompleteShip(Vec<BoardIndex>),
+    PartialShip(Vec<BoardIndex>),
+}
+
 pub trait ShipFinder {
     fn try_find_ship(&self, ix: BoardIndex) -> ShipFindingResult;
+
+    /// Finds all ship squares connected to `ix`. The ship is complete if none of its
+    /// horizontal or vertical neighbors is unknown.
+    // Note: Ships that touch each other are found as a single ship
+    fn try_find_shape(&self, ix: BoardIndex) -> ShapeFindingResult;
 }
 
 // Note: Private method returning tuple
 fn find_ship_edge(
-    board: &impl Index<BoardIndex, Output = SquareContent>,
+    board: &(impl Index<BoardIndex, Output = SquareContent> + BoardDimensions),
     current: BoardIndex,
     direction: Direction,
     prev: bool,
@@ -42,7 +55,7 @@ fn find_ship_edge(
         let next = if prev {
             current.try_previous(direction)
         } else {
-            current.try_next(direction)
+            current.try_next(direction, board.side_length())
         };
 
         // Note pattern matching with if let
@@ -50,7 +63,7 @@ fn find_ship_edge(
             current = x;
         }
 
-        if next == None || !board[current].is_ship() {
+        if next.is_none() || !board[current].is_ship() {
             break;
         }
     }
@@ -61,13 +74,13 @@ fn find_ship_edge(
 
     let complete = board[current] != SquareContent::Unknown;
     match prev {
-        true => (current.try_next(direction).unwrap(), complete),
+        true => (current.try_next(direction, board.side_length()).unwrap(), complete),
         false => (current.try_previous(direction).unwrap(), complete),
     }
 }
 
 fn try_direction(
-    board: &impl Index<BoardIndex, Output = SquareContent>,
+    board: &(impl Index<BoardIndex, Output = SquareContent> + BoardDimensions),
     ix: BoardIndex,
     direction: Direction,
 ) -> (BoardIndexRangeInclusive, bool) {
@@ -80,7 +93,7 @@ fn try_direction(
 
 impl<T> ShipFinder for T
 where
-    T: Index<BoardIndex, Output = SquareContent>,
+    T: Index<BoardIndex, Output = SquareContent> + BoardDimensions,
 {
     fn try_find_ship(&self, ix: BoardIndex) -> ShipFindingResult {
         if !self[ix].is_ship() {
@@ -102,13 +115,42 @@ where
         // When only a single square of a ship is known, no orientation can be determined and therefore the ship is only partial.
         ShipFindingResult::PartialShip(BoardIndexRangeInclusive::new(ix, ix))
     }
+
+    fn try_find_shape(&self, ix: BoardIndex) -> ShapeFindingResult {
+        if !self[ix].is_ship() {
+            return ShapeFindingResult::NoShip;
+        }
+
+        let side_length = self.side_length();
+        let mut squares = vec![ix];
+        let mut work = vec![ix];
+        let mut complete = true;
+        while let Some(current) = work.pop() {
+            for neighbor in current.neighbors4(side_length) {
+                match self[neighbor] {
+                    SquareContent::Unknown => complete = false,
+                    content if content.is_ship() && !squares.contains(&neighbor) => {
+                        squares.push(neighbor);
+                        work.push(neighbor);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        squares.sort();
+        match complete {
+            true => ShapeFindingResult::CompleteShip(squares),
+            false => ShapeFindingResult::PartialShip(squares),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use crate::{BattleshipBoardContent, ShipSetter};
+    use crate::{BattleshipBoardContent, ShipSetter, ShipShape};
 
     use super::*;
     use rstest::rstest;
@@ -119,12 +161,12 @@ mod tests {
         board.try_place_ship("A1".parse().unwrap(), 3, Direction::Horizontal).unwrap();
 
         let result =
-            find_ship_edge(&board, BoardIndex::from_index(0), Direction::Horizontal, false);
-        assert_eq!(BoardIndex::from_index(2), result.0);
+            find_ship_edge(&board, BoardIndex::from_str("A1").unwrap(), Direction::Horizontal, false);
+        assert_eq!(BoardIndex::from_str("C1").unwrap(), result.0);
         assert!(result.1);
 
-        let result = find_ship_edge(&board, BoardIndex::from_index(2), Direction::Horizontal, true);
-        assert_eq!(BoardIndex::from_index(0), result.0);
+        let result = find_ship_edge(&board, BoardIndex::from_str("C1").unwrap(), Direction::Horizontal, true);
+        assert_eq!(BoardIndex::from_str("A1").unwrap(), result.0);
         assert!(result.1);
     }
 
@@ -133,12 +175,12 @@ mod tests {
         let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
         board.try_place_ship("A1".parse().unwrap(), 3, Direction::Vertical).unwrap();
 
-        let result = find_ship_edge(&board, BoardIndex::from_index(0), Direction::Vertical, false);
-        assert_eq!(BoardIndex::from_index(20), result.0);
+        let result = find_ship_edge(&board, BoardIndex::from_str("A1").unwrap(), Direction::Vertical, false);
+        assert_eq!(BoardIndex::from_str("A3").unwrap(), result.0);
         assert!(result.1);
 
-        let result = find_ship_edge(&board, BoardIndex::from_index(20), Direction::Vertical, true);
-        assert_eq!(BoardIndex::from_index(0), result.0);
+        let result = find_ship_edge(&board, BoardIndex::from_str("A3").unwrap(), Direction::Vertical, true);
+        assert_eq!(BoardIndex::from_str("A1").unwrap(), result.0);
         assert!(result.1);
     }
 
@@ -232,10 +274,10 @@ mod tests {
                     BoardIndex::from_col_row(col + 1, row),
                     BoardIndex::from_col_row(col + 2, row),
                 ];
-                locations.into_iter().for_each(|l| {
+                locations.iter().for_each(|l| {
                     board[*l] = SquareContent::Ship;
                 });
-                locations.into_iter().for_each(|l| match board.try_find_ship(*l) {
+                locations.iter().for_each(|l| match board.try_find_ship(*l) {
                     ShipFindingResult::CompleteShip(x) if complete => assert_eq!(3, x.length()),
                     ShipFindingResult::PartialShip(x) if !complete => assert_eq!(3, x.length()),
                     _ => panic!("Invalid result"),
@@ -256,10 +298,10 @@ mod tests {
                     BoardIndex::from_col_row(col, row + 1),
                     BoardIndex::from_col_row(col, row + 2),
                 ];
-                locations.into_iter().for_each(|l| {
+                locations.iter().for_each(|l| {
                     board[*l] = SquareContent::Ship;
                 });
-                locations.into_iter().for_each(|l| match board.try_find_ship(*l) {
+                locations.iter().for_each(|l| match board.try_find_ship(*l) {
                     ShipFindingResult::CompleteShip(x) if complete => assert_eq!(3, x.length()),
                     ShipFindingResult::PartialShip(x) if !complete => assert_eq!(3, x.length()),
                     _ => panic!("Invalid result"),
@@ -267,4 +309,32 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn find_ship_on_larger_board() {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, 15);
+        board.try_place_ship("K15".parse().unwrap(), 5, Direction::Horizontal).unwrap();
+
+        assert_eq!(
+            ShipFindingResult::CompleteShip(BoardIndexRangeInclusive::new(
+                "K15".parse().unwrap(),
+                "O15".parse().unwrap()
+            )),
+            board.try_find_ship("M15".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn find_shape() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        let shape = ShipShape::new([(1, 0), (0, 1), (1, 1), (2, 1)]).unwrap();
+        board.try_place_shape("B2".parse().unwrap(), &shape).unwrap();
+
+        let squares = vec!["C2".parse().unwrap(), "B3".parse().unwrap(), "C3".parse().unwrap(), "D3".parse().unwrap()];
+        assert_eq!(ShapeFindingResult::CompleteShip(squares.clone()), board.try_find_shape("B3".parse().unwrap()));
+        assert_eq!(ShapeFindingResult::NoShip, board.try_find_shape("A1".parse().unwrap()));
+
+        board[BoardIndex::from_str("D2").unwrap()] = SquareContent::Unknown;
+        assert_eq!(ShapeFindingResult::PartialShip(squares), board.try_find_shape("C2".parse().unwrap()));
+    }
 }
@@ -0,0 +1,274 @@
+use std::{convert::TryFrom, fmt};
+
+use crate::{BoardIndex, Direction, ShipPlacement};
+
+/*
+    Learnings in this module:
+
+    * Normalizing data in constructors to make comparisons simple
+    * Geometric transformations (rotating and mirroring) on integer coordinates
+    * Searching connected squares with a work list (flood fill)
+
+    Recommended readings for this module:
+
+    * Polyominoes: https://en.wikipedia.org/wiki/Polyomino
+    * `Vec::dedup`: https://doc.rust-lang.org/std/vec/struct.Vec.html#method.dedup
+    * Flood fill: https://en.wikipedia.org/wiki/Flood_fill
+*/
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InvalidShipShape {
+    Empty,
+    /// All squares of a ship have to be connected horizontally or vertically
+    NotConnected,
+}
+
+impl fmt::Display for InvalidShipShape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidShipShape::Empty => write!(f, "A ship needs at least one square"),
+            InvalidShipShape::NotConnected => write!(f, "The squares of a ship have to be connected"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidShipShape {}
+
+/// Shape of a ship given as offsets (column, row) relative to the top left corner
+/// of the smallest rectangle containing the ship.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "Vec<(usize, usize)>", into = "Vec<(usize, usize)>"))]
+pub struct ShipShape {
+    // Note: Always normalized (sorted by row and column, no duplicates, minimum offsets are zero).
+    //       Therefore, equal shapes have equal offsets.
+    offsets: Vec<(usize, usize)>,
+}
+
+impl ShipShape {
+    pub fn new(offsets: impl IntoIterator<Item = (usize, usize)>) -> Result<ShipShape, InvalidShipShape> {
+        let shape = ShipShape::normalized(offsets.into_iter().collect());
+        if shape.offsets.is_empty() {
+            return Err(InvalidShipShape::Empty);
+        }
+
+        if !shape.is_connected() {
+            return Err(InvalidShipShape::NotConnected);
+        }
+
+        Ok(shape)
+    }
+
+    pub fn straight(ship_length: usize, direction: Direction) -> ShipShape {
+        assert!(ship_length > 0, "A ship needs at least one square");
+        ShipShape::normalized(
+            (0..ship_length)
+                .map(|i| match direction {
+                    Direction::Horizontal => (i, 0),
+                    Direction::Vertical => (0, i),
+                })
+                .collect(),
+        )
+    }
+
+    fn normalized(mut offsets: Vec<(usize, usize)>) -> ShipShape {
+        let min_col = offsets.iter().map(|o| o.0).min().unwrap_or(0);
+        let min_row = offsets.iter().map(|o| o.1).min().unwrap_or(0);
+        offsets.iter_mut().for_each(|o| *o = (o.0 - min_col, o.1 - min_row));
+        offsets.sort_unstable_by_key(|o| (o.1, o.0));
+        offsets.dedup();
+        ShipShape { offsets }
+    }
+
+    fn is_connected(&self) -> bool {
+        let mut visited = vec![self.offsets[0]];
+        let mut work = vec![self.offsets[0]];
+        while let Some((c, r)) = work.pop() {
+            for neighbor in [(c + 1, r), (c.wrapping_sub(1), r), (c, r + 1), (c, r.wrapping_sub(1))] {
+                if self.offsets.contains(&neighbor) && !visited.contains(&neighbor) {
+                    visited.push(neighbor);
+                    work.push(neighbor);
+                }
+            }
+        }
+
+        visited.len() == self.offsets.len()
+    }
+
+    /// Number of squares of the ship
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    // Note: Shapes are never empty, but clippy expects `is_empty` next to `len`
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    pub fn offsets(&self) -> &[(usize, usize)] {
+        &self.offsets
+    }
+
+    pub fn width(&self) -> usize {
+        self.offsets.iter().map(|o| o.0).max().unwrap_or(0) + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.offsets.iter().map(|o| o.1).max().unwrap_or(0) + 1
+    }
+
+    pub fn is_straight(&self) -> bool {
+        self.width() == 1 || self.height() == 1
+    }
+
+    /// Rotates the shape by 90 degrees clockwise
+    pub fn rotated(&self) -> ShipShape {
+        let height = self.height();
+        ShipShape::normalized(self.offsets.iter().map(|(c, r)| (height - 1 - r, *c)).collect())
+    }
+
+    /// Mirrors the shape at the vertical axis
+    pub fn mirrored(&self) -> ShipShape {
+        let width = self.width();
+        ShipShape::normalized(self.offsets.iter().map(|(c, r)| (width - 1 - c, *r)).collect())
+    }
+
+    /// All distinct rotations and mirrorings of the shape (including the shape itself)
+    pub fn orientations(&self) -> Vec<ShipShape> {
+        let mut result: Vec<ShipShape> = Vec::new();
+        for mut shape in [self.clone(), self.mirrored()] {
+            for _ in 0..4 {
+                if !result.contains(&shape) {
+                    result.push(shape.clone());
+                }
+
+                shape = shape.rotated();
+            }
+        }
+
+        result
+    }
+
+    /// Squares covered by the ship if the top left corner of its rectangle is at `origin`
+    pub fn squares_at(&self, origin: BoardIndex) -> impl Iterator<Item = BoardIndex> + '_ {
+        self.offsets.iter().map(move |(c, r)| BoardIndex::from_col_row(origin.column() + c, origin.row() + r))
+    }
+}
+
+impl TryFrom<Vec<(usize, usize)>> for ShipShape {
+    type Error = InvalidShipShape;
+
+    fn try_from(offsets: Vec<(usize, usize)>) -> Result<Self, Self::Error> {
+        ShipShape::new(offsets)
+    }
+}
+
+impl From<ShipShape> for Vec<(usize, usize)> {
+    fn from(shape: ShipShape) -> Self {
+        shape.offsets
+    }
+}
+
+/// Position of a ship with an arbitrary shape on the board
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShapePlacement {
+    pub origin: BoardIndex,
+    pub shape: ShipShape,
+}
+
+impl ShapePlacement {
+    pub fn new(origin: BoardIndex, shape: ShipShape) -> ShapePlacement {
+        ShapePlacement { origin, shape }
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = BoardIndex> + '_ {
+        self.shape.squares_at(self.origin)
+    }
+
+    pub fn contains(&self, ix: BoardIndex) -> bool {
+        self.squares().any(|i| i == ix)
+    }
+
+    /// Describes the ship as a [`ShipPlacement`] if it is straight
+    pub fn to_ship_placement(&self) -> Option<ShipPlacement> {
+        match (self.shape.width(), self.shape.height()) {
+            (1, height) if height > 1 => Some(ShipPlacement::new(self.origin, height, Direction::Vertical)),
+            (width, 1) => Some(ShipPlacement::new(self.origin, width, Direction::Horizontal)),
+            _ => None,
+        }
+    }
+}
+
+// Note: Straight ships are a special case of ships with arbitrary shapes
+impl From<ShipPlacement> for ShapePlacement {
+    fn from(p: ShipPlacement) -> Self {
+        ShapePlacement::new(p.start, ShipShape::straight(p.ship_length, p.direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l_shape() -> ShipShape {
+        // X
+        // X
+        // XX
+        ShipShape::new([(0, 0), (0, 1), (0, 2), (1, 2)]).unwrap()
+    }
+
+    #[test]
+    fn new_normalizes() {
+        let shape = ShipShape::new([(5, 4), (5, 3), (5, 5), (6, 5), (5, 4)]).unwrap();
+        assert_eq!(l_shape(), shape);
+        assert_eq!((2, 3), (shape.width(), shape.height()));
+        assert_eq!(4, shape.len());
+        assert!(!shape.is_straight());
+    }
+
+    #[test]
+    fn invalid_shapes() {
+        assert_eq!(Err(InvalidShipShape::Empty), ShipShape::new([]));
+        assert_eq!(Err(InvalidShipShape::NotConnected), ShipShape::new([(0, 0), (1, 1)]));
+    }
+
+    #[test]
+    fn rotate_and_mirror() {
+        // XXX
+        // X
+        assert_eq!(ShipShape::new([(0, 0), (1, 0), (2, 0), (0, 1)]).unwrap(), l_shape().rotated());
+        assert_eq!(l_shape(), l_shape().rotated().rotated().rotated().rotated());
+        assert_eq!(ShipShape::new([(1, 0), (1, 1), (1, 2), (0, 2)]).unwrap(), l_shape().mirrored());
+    }
+
+    #[test]
+    fn orientations() {
+        assert_eq!(8, l_shape().orientations().len());
+        assert_eq!(2, ShipShape::straight(3, Direction::Horizontal).orientations().len());
+        assert_eq!(1, ShipShape::straight(1, Direction::Horizontal).orientations().len());
+
+        // T shape
+        let t = ShipShape::new([(0, 0), (1, 0), (2, 0), (1, 1)]).unwrap();
+        assert_eq!(4, t.orientations().len());
+    }
+
+    #[test]
+    fn placement() {
+        let placement = ShapePlacement::new("B2".into(), l_shape());
+        assert_eq!(
+            vec![BoardIndex::from("B2"), BoardIndex::from("B3"), BoardIndex::from("B4"), BoardIndex::from("C4")],
+            placement.squares().collect::<Vec<_>>()
+        );
+        assert!(placement.contains("C4".into()));
+        assert_eq!(None, placement.to_ship_placement());
+    }
+
+    #[test]
+    fn straight_placement() {
+        let straight = ShipPlacement::new("B2".into(), 3, Direction::Vertical);
+        let placement = ShapePlacement::from(straight);
+        assert_eq!(straight.squares().collect::<Vec<_>>(), placement.squares().collect::<Vec<_>>());
+        assert_eq!(Some(straight), placement.to_ship_placement());
+    }
+}
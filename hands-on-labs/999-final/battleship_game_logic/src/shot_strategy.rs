@@ -0,0 +1,233 @@
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{BattleshipBoardContent, BoardDimensions, BoardIndex, Direction, GameState, ShipFinder, ShipFindingResult, SinglePlayerGame, SquareContent};
+
+/*
+    Learnings in this module:
+
+    * Trait objects (`dyn Trait`) for exchangeable behavior (strategy pattern)
+    * Stateful trait implementations (`&mut self`)
+    * Iterator adapters (`filter`, `flat_map`, `find`)
+
+    Recommended readings for this module:
+
+    * Trait objects: https://doc.rust-lang.org/book/ch17-02-trait-objects.html
+    * Strategy pattern in Rust: https://rust-unofficial.github.io/patterns/patterns/behavioural/strategy.html
+    * Iterator adapters: https://doc.rust-lang.org/std/iter/trait.Iterator.html#provided-methods
+*/
+
+pub trait ShotStrategy {
+    /// Picks the next square to shoot at. Returns `None` if there is no unknown square left.
+    fn next_shot(&mut self, shooting_board: &BattleshipBoardContent) -> Option<BoardIndex>;
+}
+
+fn unknown_squares(shooting_board: &BattleshipBoardContent) -> impl Iterator<Item = BoardIndex> + '_ {
+    let side_length = shooting_board.side_length();
+    (0..shooting_board.size())
+        .map(move |ix| BoardIndex::from_index(ix, side_length))
+        .filter(|ix| shooting_board[*ix] == SquareContent::Unknown)
+}
+
+// Note: We sample `u32` values to get the same shots for a given seed on every platform
+fn pick_random(candidates: &[BoardIndex], rng: &mut dyn RngCore) -> Option<BoardIndex> {
+    match candidates.len() {
+        0 => None,
+        len => Some(candidates[rng.gen_range(0..len as u32) as usize]),
+    }
+}
+
+/// Shoots at random squares that have not been shot at before
+#[derive(Debug, Clone)]
+pub struct RandomStrategy {
+    rng: ChaCha8Rng,
+}
+
+impl RandomStrategy {
+    /// Creates a strategy that always picks the same shots for a given seed
+    pub fn with_seed(seed: u64) -> RandomStrategy {
+        RandomStrategy { rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+}
+
+impl Default for RandomStrategy {
+    fn default() -> Self {
+        RandomStrategy::with_seed(rand::random())
+    }
+}
+
+impl ShotStrategy for RandomStrategy {
+    fn next_shot(&mut self, shooting_board: &BattleshipBoardContent) -> Option<BoardIndex> {
+        pick_random(&unknown_squares(shooting_board).collect::<Vec<_>>(), &mut self.rng)
+    }
+}
+
+/// Hunts for ships on a checkerboard pattern. As soon as a ship has been hit,
+/// it targets the squares around the hit until the ship is sunk.
+#[derive(Debug, Clone)]
+pub struct HuntTargetStrategy {
+    rng: ChaCha8Rng,
+}
+
+impl HuntTargetStrategy {
+    /// Creates a strategy that always picks the same shots for a given seed
+    pub fn with_seed(seed: u64) -> HuntTargetStrategy {
+        HuntTargetStrategy { rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    fn target(shooting_board: &BattleshipBoardContent) -> Option<BoardIndex> {
+        let side_length = shooting_board.side_length();
+        let is_unknown = |ix: &BoardIndex| shooting_board[*ix] == SquareContent::Unknown;
+
+        let hits = (0..shooting_board.size())
+            .map(|ix| BoardIndex::from_index(ix, side_length))
+            .filter(|ix| shooting_board[*ix] == SquareContent::HitShip);
+        for hit in hits {
+            let range = match shooting_board.try_find_ship(hit) {
+                ShipFindingResult::PartialShip(r) | ShipFindingResult::CompleteShip(r) => r,
+                ShipFindingResult::NoShip => continue,
+            };
+
+            // If we know the orientation of the ship, continue at one of its ends
+            if range.length() > 1 {
+                let squares = range.into_iter().collect::<Vec<_>>();
+                let (first, last) = (squares[0], squares[squares.len() - 1]);
+                let direction = if first.row() == last.row() { Direction::Horizontal } else { Direction::Vertical };
+                if let Some(ix) = [first.try_previous(direction), last.try_next(direction, side_length)]
+                    .into_iter()
+                    .flatten()
+                    .find(is_unknown)
+                {
+                    return Some(ix);
+                }
+            }
+
+            // Orientation is unknown (or both ends are blocked) -> try all neighbors
+            if let Some(ix) = [Direction::Horizontal, Direction::Vertical]
+                .into_iter()
+                .flat_map(|d| [hit.try_previous(d), hit.try_next(d, side_length)])
+                .flatten()
+                .find(is_unknown)
+            {
+                return Some(ix);
+            }
+        }
+
+        None
+    }
+
+    fn hunt(&mut self, shooting_board: &BattleshipBoardContent) -> Option<BoardIndex> {
+        // Note: Every ship with a length of two or more covers at least one
+        //       square with an even sum of column and row.
+        let unknown = unknown_squares(shooting_board).collect::<Vec<_>>();
+        let even = unknown.iter().copied().filter(|ix| (ix.column() + ix.row()) % 2 == 0).collect::<Vec<_>>();
+        pick_random(&even, &mut self.rng).or_else(|| pick_random(&unknown, &mut self.rng))
+    }
+}
+
+impl Default for HuntTargetStrategy {
+    fn default() -> Self {
+        HuntTargetStrategy::with_seed(rand::random())
+    }
+}
+
+impl ShotStrategy for HuntTargetStrategy {
+    fn next_shot(&mut self, shooting_board: &BattleshipBoardContent) -> Option<BoardIndex> {
+        HuntTargetStrategy::target(shooting_board).or_else(|| self.hunt(shooting_board))
+    }
+}
+
+/// Lets the strategy play until the game is over. Returns the final state of the game.
+pub fn auto_play(game: &mut SinglePlayerGame, strategy: &mut dyn ShotStrategy) -> GameState {
+    while game.game_state() == GameState::InProgress {
+        match strategy.next_shot(game.shooting_board()) {
+            Some(ix) => game.shoot(ix),
+            None => break,
+        };
+    }
+
+    game.game_state()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{GameRules, ShipSetter};
+
+    use super::*;
+    use rstest::rstest;
+
+    fn get_game() -> SinglePlayerGame {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("B2".into(), 5, Direction::Horizontal).unwrap();
+        board.try_place_ship("J1".into(), 4, Direction::Vertical).unwrap();
+        board.try_place_ship("A10".into(), 3, Direction::Horizontal).unwrap();
+        board.try_place_ship("E5".into(), 3, Direction::Vertical).unwrap();
+        board.try_place_ship("H8".into(), 2, Direction::Horizontal).unwrap();
+        SinglePlayerGame::with_board(board, GameRules::default())
+    }
+
+    #[test]
+    fn random_shoots_unknown_square() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board[BoardIndex::from("F7")] = SquareContent::Unknown;
+        assert_eq!(Some(BoardIndex::from("F7")), RandomStrategy::default().next_shot(&board));
+    }
+
+    #[rstest]
+    #[case(&mut RandomStrategy::default())]
+    #[case(&mut HuntTargetStrategy::default())]
+    fn no_square_left(#[case] strategy: &mut dyn ShotStrategy) {
+        let board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        assert_eq!(None, strategy.next_shot(&board));
+    }
+
+    #[test]
+    fn target_around_single_hit() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board[BoardIndex::from("E5")] = SquareContent::HitShip;
+        board[BoardIndex::from("E6")] = SquareContent::Unknown;
+        assert_eq!(Some(BoardIndex::from("E6")), HuntTargetStrategy::default().next_shot(&board));
+    }
+
+    #[test]
+    fn target_along_known_orientation() {
+        let mut board = BattleshipBoardContent::new();
+        board[BoardIndex::from("E5")] = SquareContent::HitShip;
+        board[BoardIndex::from("F5")] = SquareContent::HitShip;
+        board[BoardIndex::from("D5")] = SquareContent::Water;
+        assert_eq!(Some(BoardIndex::from("G5")), HuntTargetStrategy::default().next_shot(&board));
+    }
+
+    #[test]
+    fn hunt_on_checkerboard() {
+        let board = BattleshipBoardContent::new();
+        let mut strategy = HuntTargetStrategy::default();
+        for _ in 0..20 {
+            let ix = strategy.next_shot(&board).unwrap();
+            assert_eq!(0, (ix.column() + ix.row()) % 2);
+        }
+    }
+
+    #[rstest]
+    #[case(&mut RandomStrategy::default())]
+    #[case(&mut HuntTargetStrategy::default())]
+    fn auto_play_sinks_all_ships(#[case] strategy: &mut dyn ShotStrategy) {
+        let mut game = get_game();
+        assert_eq!(GameState::AllShipsSunken, auto_play(&mut game, strategy));
+        assert!(game.log().count() <= 100);
+        assert!(game.log().all(|s| s.result != SquareContent::Unknown));
+    }
+
+    #[test]
+    fn same_seed_same_shots() {
+        let play = |mut strategy: Box<dyn ShotStrategy>| {
+            let mut game = get_game();
+            auto_play(&mut game, strategy.as_mut());
+            game.log().map(|s| s.location).collect::<Vec<_>>()
+        };
+
+        assert_eq!(play(Box::new(RandomStrategy::with_seed(42))), play(Box::new(RandomStrategy::with_seed(42))));
+        assert_eq!(play(Box::new(HuntTargetStrategy::with_seed(42))), play(Box::new(HuntTargetStrategy::with_seed(42))));
+        assert_ne!(play(Box::new(RandomStrategy::with_seed(1))), play(Box::new(RandomStrategy::with_seed(2))));
+    }
+}
@@ -0,0 +1,340 @@
+use std::{io::Write, thread};
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    auto_play, placement_rng, GameRules, GameState, GameStatistics, PlacementError, ShipPlacer,
+    ShotStrategy, SinglePlayerGame,
+};
+
+/*
+    Learnings in this module:
+
+    * Running work on multiple threads with scoped threads
+    * Deterministic results independent of the number of threads
+    * Function pointers as factories
+    * Writing to any `Write` implementation (files, stdout, buffers)
+
+    Recommended readings for this module:
+
+    * Scoped threads: https://doc.rust-lang.org/std/thread/fn.scope.html
+    * Function pointers: https://doc.rust-lang.org/book/ch19-05-advanced-functions-and-closures.html#function-pointers
+    * `Write` trait: https://doc.rust-lang.org/std/io/trait.Write.html
+    * Percentiles: https://en.wikipedia.org/wiki/Percentile#The_nearest-rank_method
+*/
+
+/// Creates the shot strategy for a single game
+pub type ShooterFactory = fn(rules: &GameRules, seed: u64) -> Box<dyn ShotStrategy>;
+
+/// Plays many games with the same placer and shooter
+pub struct Simulation {
+    pub rules: GameRules,
+    pub placer: ShipPlacer,
+    pub shooter: ShooterFactory,
+    pub games: usize,
+    pub threads: usize,
+    /// Seed from which the seeds of all games are derived
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOutcome {
+    /// Number of the game, starting with 0
+    pub game: usize,
+    pub placement_seed: u64,
+    pub shot_seed: u64,
+    pub statistics: GameStatistics,
+}
+
+impl Simulation {
+    pub fn new(
+        rules: GameRules,
+        placer: ShipPlacer,
+        shooter: ShooterFactory,
+        games: usize,
+        seed: u64,
+    ) -> Simulation {
+        Simulation { rules, placer, shooter, games, threads: 1, seed }
+    }
+
+    /// Plays all games and returns their outcomes in the order of the games.
+    ///
+    /// The outcomes only depend on the seed, not on the number of threads.
+    pub fn run(&self) -> Result<Vec<GameOutcome>, PlacementError> {
+        // Note: Seeds are drawn up front, so every game gets the same seeds no matter which thread plays it
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let seeds = (0..self.games).map(|_| (rng.next_u64(), rng.next_u64())).collect::<Vec<_>>();
+
+        let threads = self.threads.clamp(1, self.games.max(1));
+        let mut outcomes = thread::scope(|scope| {
+            let workers = (0..threads)
+                .map(|first| {
+                    let seeds = &seeds;
+                    scope.spawn(move || {
+                        (first..seeds.len())
+                            .step_by(threads)
+                            .map(|game| self.play(game, seeds[game].0, seeds[game].1))
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            // Note: `join` only fails if the thread panicked. We pass the panic on.
+            workers.into_iter().map(|w| w.join().unwrap()).collect::<Result<Vec<_>, _>>()
+        })?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        outcomes.sort_unstable_by_key(|o| o.game);
+        Ok(outcomes)
+    }
+
+    fn play(
+        &self,
+        game: usize,
+        placement_seed: u64,
+        shot_seed: u64,
+    ) -> Result<GameOutcome, PlacementError> {
+        let mut single_player_game = SinglePlayerGame::try_with_placer(
+            self.rules.clone(),
+            self.placer,
+            &mut placement_rng(placement_seed),
+        )?;
+        let mut strategy = (self.shooter)(&self.rules, shot_seed);
+        auto_play(&mut single_player_game, strategy.as_mut());
+        Ok(GameOutcome {
+            game,
+            placement_seed,
+            shot_seed,
+            statistics: GameStatistics::from_game(&single_player_game),
+        })
+    }
+}
+
+/// Distribution of the number of shots needed to win
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ShotDistribution {
+    // Note: Sorted, which makes median and percentiles simple lookups
+    shots: Vec<usize>,
+}
+
+impl ShotDistribution {
+    /// Collects the shots of all games that have been won
+    pub fn new<'a>(outcomes: impl IntoIterator<Item = &'a GameOutcome>) -> ShotDistribution {
+        let mut shots = outcomes
+            .into_iter()
+            .filter(|o| o.statistics.game_state == GameState::AllShipsSunken)
+            .map(|o| o.statistics.shots)
+            .collect::<Vec<_>>();
+        shots.sort_unstable();
+        ShotDistribution { shots }
+    }
+
+    /// Number of games won
+    pub fn len(&self) -> usize {
+        self.shots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shots.is_empty()
+    }
+
+    pub fn min(&self) -> Option<usize> {
+        self.shots.first().copied()
+    }
+
+    pub fn max(&self) -> Option<usize> {
+        self.shots.last().copied()
+    }
+
+    pub fn mean(&self) -> Option<f32> {
+        match self.shots.len() {
+            0 => None,
+            len => Some(self.shots.iter().sum::<usize>() as f32 / len as f32),
+        }
+    }
+
+    pub fn median(&self) -> Option<f32> {
+        let len = self.shots.len();
+        match len {
+            0 => None,
+            _ if len % 2 == 1 => Some(self.shots[len / 2] as f32),
+            _ => Some((self.shots[len / 2 - 1] + self.shots[len / 2]) as f32 / 2.0),
+        }
+    }
+
+    /// Smallest number of shots that at least `percent` percent of the won games needed (nearest-rank method)
+    pub fn percentile(&self, percent: f32) -> Option<usize> {
+        if self.shots.is_empty() {
+            return None;
+        }
+
+        let rank = (percent.clamp(0.0, 100.0) / 100.0 * self.shots.len() as f32).ceil() as usize;
+        Some(self.shots[rank.max(1) - 1])
+    }
+
+    /// Number of games per bucket of `bucket_width` shots. Returns the first number of shots of each bucket
+    /// and the number of games, including empty buckets between the minimum and the maximum.
+    pub fn histogram(&self, bucket_width: usize) -> Vec<(usize, usize)> {
+        let bucket_width = bucket_width.max(1);
+        let (min, max) = match (self.min(), self.max()) {
+            (Some(min), Some(max)) => (min / bucket_width, max / bucket_width),
+            _ => return Vec::new(),
+        };
+
+        let mut result = (min..=max).map(|bucket| (bucket * bucket_width, 0)).collect::<Vec<_>>();
+        for shots in self.shots.iter() {
+            result[shots / bucket_width - min].1 += 1;
+        }
+
+        result
+    }
+}
+
+/// Writes one line per game with its seeds and statistics
+pub fn write_csv<'a>(
+    outcomes: impl IntoIterator<Item = &'a GameOutcome>,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    writeln!(writer, "game,placement_seed,shot_seed,game_state,shots,hits,misses,repeat_shots,longest_miss_streak")?;
+    for o in outcomes {
+        let s = &o.statistics;
+        writeln!(
+            writer,
+            "{},{},{},{:?},{},{},{},{},{}",
+            o.game,
+            o.placement_seed,
+            o.shot_seed,
+            s.game_state,
+            s.shots,
+            s.hits,
+            s.misses,
+            s.repeat_shots,
+            s.longest_miss_streak
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::{
+        backtracking_placer, random_placer, ErrorKind, HuntTargetStrategy, RandomStrategy,
+    };
+
+    use super::*;
+
+    fn hunt_target(_: &GameRules, seed: u64) -> Box<dyn ShotStrategy> {
+        Box::new(HuntTargetStrategy::with_seed(seed))
+    }
+
+    fn distribution(shots: &[usize]) -> ShotDistribution {
+        let mut shots = shots.to_vec();
+        shots.sort_unstable();
+        ShotDistribution { shots }
+    }
+
+    #[test]
+    fn reproducible_with_any_number_of_threads() {
+        let mut simulation =
+            Simulation::new(GameRules::default(), backtracking_placer, hunt_target, 20, 42);
+        let single_threaded = simulation.run().unwrap();
+        simulation.threads = 3;
+        let multi_threaded = simulation.run().unwrap();
+
+        assert_eq!(20, single_threaded.len());
+        assert_eq!(single_threaded, multi_threaded);
+        assert!(single_threaded
+            .iter()
+            .all(|o| o.statistics.game_state == GameState::AllShipsSunken));
+        assert!(single_threaded.iter().enumerate().all(|(i, o)| o.game == i));
+    }
+
+    #[test]
+    fn different_strategies() {
+        let random = Simulation::new(
+            GameRules::default(),
+            random_placer,
+            |_, seed| Box::new(RandomStrategy::with_seed(seed)),
+            10,
+            1,
+        )
+        .run()
+        .unwrap();
+        let hunt_target =
+            Simulation::new(GameRules::default(), random_placer, hunt_target, 10, 1).run().unwrap();
+
+        // Note: Same seed, same fleets
+        assert_eq!(
+            random.iter().map(|o| o.placement_seed).collect::<Vec<_>>(),
+            hunt_target.iter().map(|o| o.placement_seed).collect::<Vec<_>>()
+        );
+        assert!(ShotDistribution::new(&hunt_target).mean() < ShotDistribution::new(&random).mean());
+    }
+
+    #[test]
+    fn placement_error() {
+        let rules = GameRules { side_length: 4, ..Default::default() };
+        let simulation = Simulation::new(rules, backtracking_placer, hunt_target, 2, 42);
+        assert_eq!(ErrorKind::ShipTooLong, simulation.run().unwrap_err().error_kind());
+    }
+
+    #[test]
+    fn no_games() {
+        let simulation =
+            Simulation::new(GameRules::default(), backtracking_placer, hunt_target, 0, 42);
+        assert!(simulation.run().unwrap().is_empty());
+        assert_eq!(None, ShotDistribution::default().mean());
+        assert_eq!(None, ShotDistribution::default().percentile(50.0));
+        assert!(ShotDistribution::default().histogram(5).is_empty());
+    }
+
+    #[rstest]
+    #[case(&[50, 40, 60], 50.0)]
+    #[case(&[40, 50, 60, 70], 55.0)]
+    fn median(#[case] shots: &[usize], #[case] expected: f32) {
+        assert_eq!(Some(expected), distribution(shots).median());
+    }
+
+    #[rstest]
+    #[case(0.0, 1)]
+    #[case(10.0, 1)]
+    #[case(25.0, 3)]
+    #[case(50.0, 5)]
+    #[case(90.0, 9)]
+    #[case(100.0, 10)]
+    fn percentile(#[case] percent: f32, #[case] expected: usize) {
+        let distribution = distribution(&(1..=10).collect::<Vec<_>>());
+        assert_eq!(Some(expected), distribution.percentile(percent));
+        assert_eq!(Some(5.5), distribution.mean());
+    }
+
+    #[test]
+    fn histogram() {
+        assert_eq!(vec![(40, 2), (45, 0), (50, 1)], distribution(&[42, 44, 50]).histogram(5));
+        assert_eq!(vec![(42, 1), (43, 0), (44, 1)], distribution(&[42, 44]).histogram(1));
+    }
+
+    #[test]
+    fn csv() {
+        let simulation =
+            Simulation::new(GameRules::default(), backtracking_placer, hunt_target, 2, 42);
+        let outcomes = simulation.run().unwrap();
+        let mut buffer = Vec::new();
+        write_csv(&outcomes, &mut buffer).unwrap();
+
+        let csv = String::from_utf8(buffer).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(3, lines.len());
+        assert!(lines[0].starts_with("game,placement_seed,shot_seed,game_state,shots"));
+        assert!(lines[1].starts_with(&format!(
+            "0,{},{},AllShipsSunken,{},17,",
+            outcomes[0].placement_seed, outcomes[0].shot_seed, outcomes[0].statistics.shots
+        )));
+    }
+}
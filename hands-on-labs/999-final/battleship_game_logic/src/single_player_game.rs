@@ -1,50 +1,222 @@
 use std::ops::Index;
 
-use crate::{BattleshipBoardContent, BoardFiller, BoardIndex, ShipFinder, ShipFindingResult, SquareContent, ToCompactString, random_placer};
+use rand::RngCore;
+
+use crate::{AdjacencyRule, BattleshipBoardContent, BoardDimensions, BoardFiller, BoardIndex, ErrorKind, Fleet, FiringMode, GameEvent, GameRules, PlacementError, PlacementStrategy, RepeatShotRule, PlacementRecorder, RuleAwareSetter, Ship, ShipPlacer, SquareContent, ToCompactString, backtracking_placer, placement_rng};
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shot {
     pub location: BoardIndex,
     pub result: SquareContent,
 }
 
+/// Result of a shot accepted by `try_shoot`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShotOutcome {
+    pub result: SquareContent,
+    pub events: Vec<GameEvent>,
+}
+
+/// Result of a volley accepted by `shoot_salvo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolleyOutcome {
+    /// Results in the order of the shots in the volley
+    pub results: Vec<SquareContent>,
+    pub events: Vec<GameEvent>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShotError {
+    /// The square has already been shot at and the rules reject repeat shots
+    AlreadyShot(BoardIndex),
+    /// The game is over, the state of the game is attached
+    GameFinished(GameState),
+    OutOfBounds(BoardIndex),
+    /// A volley must contain at least one and at most `allowed` shots
+    InvalidVolleySize { size: usize, allowed: usize },
+    /// The square is targeted more than once in the same volley
+    DuplicateShot(BoardIndex),
+}
+
+impl std::fmt::Display for ShotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShotError::AlreadyShot(ix) => write!(f, "{} has already been shot at", ix),
+            ShotError::GameFinished(state) => write!(f, "The game is already finished ({:?})", state),
+            ShotError::OutOfBounds(ix) => write!(f, "{} is outside of the board", ix),
+            ShotError::InvalidVolleySize { size, allowed } => {
+                write!(f, "A volley must contain between 1 and {} shots, got {}", allowed, size)
+            }
+            ShotError::DuplicateShot(ix) => write!(f, "{} is targeted more than once", ix),
+        }
+    }
+}
+
+impl std::error::Error for ShotError {}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum GameState {
     InProgress,
     AllShipsSunken,
     TooManyShots,
+    // Note: The following states are only used by two-player games
+    PlayerOneWon,
+    PlayerTwoWon,
 }
 
+// Note: Saved games use a versioned format that is independent of the
+//       fields of this struct (see `serialization` module).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "crate::serialization::SavedGame", try_from = "crate::serialization::SavedGame"))]
 pub struct SinglePlayerGame {
     log: Vec::<Shot>,
+    // Note: Positions in the log at which the volleys start. Every call to `shoot`
+    //       is a volley of its own.
+    volleys: Vec<usize>,
     board: BattleshipBoardContent,
+    fleet: Fleet,
     shooting_board: BattleshipBoardContent,
+    rules: GameRules,
+    seed: Option<u64>,
 }
 
-const SHIPS: &[usize] = &[5, 4, 3, 3, 2];
-
 impl SinglePlayerGame {
     pub fn new() -> SinglePlayerGame {
-        SinglePlayerGame::new_internal(true)
+        SinglePlayerGame::with_rules(GameRules::default())
+    }
+
+    pub fn with_side_length(side_length: usize) -> SinglePlayerGame {
+        SinglePlayerGame::with_rules(GameRules { side_length, ..Default::default() })
+    }
+
+    // Note: Panics if the fleet does not fit on the board. Use `try_with_rules`
+    //       if the rules come from an untrusted source.
+    pub fn with_rules(rules: GameRules) -> SinglePlayerGame {
+        SinglePlayerGame::try_with_rules(rules).expect("Fleet does not fit on the board")
+    }
+
+    pub fn try_with_rules(rules: GameRules) -> Result<SinglePlayerGame, PlacementError> {
+        SinglePlayerGame::try_with_seed(rules, rand::random())
+    }
+
+    /// Creates a game whose fleet layout only depends on the rules and the seed
+    pub fn with_seed(rules: GameRules, seed: u64) -> SinglePlayerGame {
+        SinglePlayerGame::try_with_seed(rules, seed).expect("Fleet does not fit on the board")
+    }
+
+    pub fn try_with_seed(rules: GameRules, seed: u64) -> Result<SinglePlayerGame, PlacementError> {
+        let mut game = SinglePlayerGame::try_with_rng(rules, &mut placement_rng(seed))?;
+        game.seed = Some(seed);
+        Ok(game)
+    }
+
+    pub fn with_rng(rules: GameRules, rng: &mut dyn RngCore) -> SinglePlayerGame {
+        SinglePlayerGame::try_with_rng(rules, rng).expect("Fleet does not fit on the board")
     }
 
-    fn new_internal(fill: bool) -> SinglePlayerGame {
-        let mut game = SinglePlayerGame {
+    pub fn try_with_rng(rules: GameRules, rng: &mut dyn RngCore) -> Result<SinglePlayerGame, PlacementError> {
+        SinglePlayerGame::try_with_placer(rules, backtracking_placer, rng)
+    }
+
+    /// Creates a game whose ships are placed by the given placer (e.g. to compare placement strategies)
+    pub fn try_with_placer(rules: GameRules, placer: ShipPlacer, rng: &mut dyn RngCore) -> Result<SinglePlayerGame, PlacementError> {
+        // Note: Placers could not place an empty ship anyway, and building the fleet would panic
+        if rules.ship_lengths.contains(&0) {
+            return Err(PlacementError::new(ErrorKind::EmptyShip));
+        }
+
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, rules.side_length);
+        let mut setter = RuleAwareSetter::new(&mut board, rules.adjacency);
+        let mut recorder = PlacementRecorder::new(&mut setter);
+        recorder.fill_with_rng(&rules.ship_lengths, placer, rng)?;
+        Ok(SinglePlayerGame::with_fleet(recorder.into_fleet(), rules))
+    }
+
+    /// Like `try_with_placer`, but for placers with their own configuration and random number generator
+    pub fn try_with_strategy(rules: GameRules, strategy: &mut dyn PlacementStrategy) -> Result<SinglePlayerGame, PlacementError> {
+        let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, rules.side_length);
+        let mut setter = RuleAwareSetter::new(&mut board, rules.adjacency);
+        let mut recorder = PlacementRecorder::new(&mut setter);
+        recorder.fill_with_strategy(&rules.ship_lengths, strategy)?;
+        Ok(SinglePlayerGame::with_fleet(recorder.into_fleet(), rules))
+    }
+
+    // Note: Visible for other modules of this crate, but not for users of the crate
+    pub(crate) fn with_board(board: BattleshipBoardContent, rules: GameRules) -> SinglePlayerGame {
+        assert_eq!(board.side_length(), rules.side_length, "Side length of the board does not match the rules");
+        SinglePlayerGame::with_fleet(Fleet::from_board(&board), rules)
+    }
+
+    /// Creates a game with a given fleet (e.g. a custom scenario with ships of arbitrary shapes).
+    // Note: The fleet is expected to follow the rules. `ship_lengths` holds the number of squares per ship.
+    pub fn with_fleet(fleet: Fleet, rules: GameRules) -> SinglePlayerGame {
+        SinglePlayerGame {
             log: Vec::new(),
-            board: BattleshipBoardContent::new_initialized(SquareContent::Water),
-            shooting_board: BattleshipBoardContent::new(),
-        };
-        if fill { game.board.fill(SHIPS, random_placer); }
-        game
+            volleys: Vec::new(),
+            board: fleet.to_board(rules.side_length),
+            shooting_board: BattleshipBoardContent::new_with_side_length(rules.side_length),
+            fleet,
+            rules,
+            seed: None,
+        }
     }
 
-    pub fn board(&self) -> &impl Index<BoardIndex, Output = SquareContent> {
+    #[cfg(feature = "serde")]
+    pub(crate) fn fleet_board(&self) -> &BattleshipBoardContent {
         &self.board
     }
 
-    pub fn shooting_board(&self) -> &impl Index<BoardIndex, Output = SquareContent> {
+    #[cfg(feature = "serde")]
+    pub(crate) fn volley_starts(&self) -> &[usize] {
+        &self.volleys
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_volley_starts(&mut self, volleys: Vec<usize>) {
+        self.volleys = volleys;
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    #[cfg(test)]
+    fn without_ships(rules: GameRules) -> SinglePlayerGame {
+        SinglePlayerGame::with_fleet(Fleet::default(), rules)
+    }
+
+    pub fn side_length(&self) -> usize {
+        self.board.side_length()
+    }
+
+    pub fn rules(&self) -> &GameRules {
+        &self.rules
+    }
+
+    /// Seed that has been used to place the ships (`None` if ships were placed otherwise)
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn board(&self) -> &(impl Index<BoardIndex, Output = SquareContent> + BoardDimensions) {
+        &self.board
+    }
+
+    pub fn fleet(&self) -> &Fleet {
+        &self.fleet
+    }
+
+    /// Ship on the given square if it has been sunk (e.g. to tell the player "you sank my Cruiser")
+    pub fn sunken_ship_at(&self, ix: BoardIndex) -> Option<&Ship> {
+        self.fleet.ship_at(ix).filter(|s| s.is_sunk())
+    }
+
+    pub fn shooting_board(&self) -> &BattleshipBoardContent {
         &self.shooting_board
     }
 
@@ -52,63 +224,270 @@ impl SinglePlayerGame {
         self.shooting_board.to_compact_str()
     }
 
+    // Note: `shoot` accepts every shot on the board, even after the game is over.
+    //       Use `try_shoot` to enforce the rules.
     pub fn shoot(&mut self, ix: BoardIndex) -> SquareContent {
-        let mut content;
-        match self.shooting_board[ix] {
+        self.volleys.push(self.log.len());
+        self.fire(ix).0
+    }
+
+    fn reveals_water(&self) -> bool {
+        self.rules.reveal_water && self.rules.adjacency == AdjacencyRule::NoTouching
+    }
+
+    // Note: Returns the squares that have been revealed as water in addition to the result
+    fn fire(&mut self, ix: BoardIndex) -> (SquareContent, Vec<BoardIndex>) {
+        let reveals_water = self.reveals_water();
+        let mut revealed = Vec::new();
+        let content = match self.shooting_board[ix] {
             SquareContent::Unknown => {
                 // Player shoots on a square that she hasn't shot at before
-                content = self.board[ix];
-                self.shooting_board[ix] = content;
-                if content == SquareContent::Ship {
-                    // We have a hit
-                    content = SquareContent::HitShip;
-                    self.shooting_board[ix] = SquareContent::HitShip;
-
-                    // Check whether the hit sank the ship
-                    if let ShipFindingResult::CompleteShip(r) = self.board.try_find_ship(ix) {
-                        if r.clone().into_iter().all(|i| matches!(self.shooting_board[i], SquareContent::HitShip | SquareContent::SunkenShip)) {
-                            // The hit sank the ship -> change all ship quares to SunkenShip
-                            content = SquareContent::SunkenShip;
-                            for i in r {
-                                self.shooting_board[i] = SquareContent::SunkenShip;
-                            }
+                let content = match self.fleet.hit(ix) {
+                    None => SquareContent::Water,
+                    Some(ship) if ship.is_sunk() => {
+                        // The hit sank the ship -> change all ship squares to SunkenShip
+                        for i in ship.squares() {
+                            self.shooting_board[i] = SquareContent::SunkenShip;
                         }
-                    }
 
-                }
+                        if reveals_water {
+                            revealed = reveal_water(&mut self.shooting_board, ship.squares());
+                        }
+
+                        SquareContent::SunkenShip
+                    }
+                    Some(_) => SquareContent::HitShip,
+                };
+                self.shooting_board[ix] = content;
+                content
             },
-            _ => content = self.shooting_board[ix],
+            content => content,
         };
 
         self.log.push(Shot { location: ix, result: content });
-        content
+        (content, revealed)
     }
 
-    pub fn game_state(&self) -> GameState {
-        self.game_state_internal(SHIPS)
+    /// Shoots like `shoot` and describes what happened
+    pub fn shoot_with_events(&mut self, ix: BoardIndex) -> Vec<GameEvent> {
+        let state_before = self.game_state();
+        self.volleys.push(self.log.len());
+        let mut events = self.fire_with_events(ix);
+        events.extend(self.game_over_event(state_before));
+        events
     }
 
-    pub fn game_state_internal(&self, ships: &[usize]) -> GameState {
-        if self.log.len() > 100 {
-            return GameState::TooManyShots;
+    fn fire_with_events(&mut self, ix: BoardIndex) -> Vec<GameEvent> {
+        let repeat = self.shooting_board[ix] != SquareContent::Unknown;
+        let (result, revealed) = self.fire(ix);
+
+        let mut events = vec![match (repeat, result) {
+            (true, _) => GameEvent::RepeatShot(ix),
+            (false, SquareContent::Water) => GameEvent::Miss(ix),
+            (false, _) => GameEvent::Hit(ix),
+        }];
+
+        if !repeat && result == SquareContent::SunkenShip {
+            if let Some(ship) = self.fleet.ship_at(ix) {
+                events.push(GameEvent::Sunk { ship: ship.kind(), squares: ship.squares().collect() });
+            }
+        }
+
+        if !revealed.is_empty() {
+            events.push(GameEvent::WaterRevealed(revealed));
+        }
+
+        events
+    }
+
+    fn game_over_event(&self, state_before: GameState) -> Option<GameEvent> {
+        let state = self.game_state();
+        match state != state_before && state != GameState::InProgress {
+            true => Some(GameEvent::GameOver(state)),
+            false => None,
+        }
+    }
+
+    fn check_shot(&self, ix: BoardIndex) -> Result<(), ShotError> {
+        // Note: Shots outside of the board are reported as such, even if the game is over
+        let state = self.game_state();
+        if state != GameState::InProgress && self.board.contains(ix) {
+            return Err(ShotError::GameFinished(state));
+        }
+
+        self.check_square(ix)
+    }
+
+    // Note: Does not check whether the game is over. In two-player games, this
+    //       depends on the opponent, too.
+    pub(crate) fn check_square(&self, ix: BoardIndex) -> Result<(), ShotError> {
+        if !self.board.contains(ix) {
+            return Err(ShotError::OutOfBounds(ix));
+        }
+
+        if self.rules.repeat_shots == RepeatShotRule::Reject && self.shooting_board[ix] != SquareContent::Unknown {
+            return Err(ShotError::AlreadyShot(ix));
+        }
+
+        Ok(())
+    }
+
+    /// Shoots if the shot is allowed by the rules
+    pub fn try_shoot(&mut self, ix: BoardIndex) -> Result<ShotOutcome, ShotError> {
+        self.check_shot(ix)?;
+        let events = self.shoot_with_events(ix);
+        Ok(ShotOutcome { result: self.shooting_board[ix], events })
+    }
+
+    /// Fires a volley. In salvo mode, the volley may contain one shot per ship that has not been sunk yet.
+    // Note: In a single player game, the player does not have a fleet of her own. Therefore,
+    //       the number of shots depends on the fleet she is shooting at.
+    pub fn shoot_salvo(&mut self, shots: &[BoardIndex]) -> Result<VolleyOutcome, ShotError> {
+        let state = self.game_state();
+        if state != GameState::InProgress {
+            return Err(ShotError::GameFinished(state));
+        }
+
+        let allowed = self.fleet.ships().iter().filter(|s| !s.is_sunk()).count();
+        self.fire_volley(shots, allowed)
+    }
+
+    /// Fires a volley without checking whether the game is over. The caller has to check that.
+    pub(crate) fn fire_volley(&mut self, shots: &[BoardIndex], ships_afloat: usize) -> Result<VolleyOutcome, ShotError> {
+        let state_before = self.game_state();
+
+        // Note: A volley must not exceed the shot limit, which still counts single shots
+        let shots_left = self.rules.max_shots.saturating_sub(self.log.len());
+        let allowed = match self.rules.firing_mode {
+            FiringMode::OneShotPerTurn => 1,
+            FiringMode::Salvo => ships_afloat,
+        }
+        .min(shots_left);
+        if shots.is_empty() || shots.len() > allowed {
+            return Err(ShotError::InvalidVolleySize { size: shots.len(), allowed });
+        }
+
+        // Note: The whole volley is validated before the first shot is fired
+        for (i, ix) in shots.iter().enumerate() {
+            self.check_square(*ix)?;
+            if shots[..i].contains(ix) {
+                return Err(ShotError::DuplicateShot(*ix));
+            }
+        }
+
+        self.volleys.push(self.log.len());
+        let mut events = Vec::new();
+        for ix in shots.iter() {
+            events.extend(self.fire_with_events(*ix));
         }
 
-        if self.shooting_board.iter().filter(|s| { matches!(s, SquareContent::HitShip | SquareContent::SunkenShip)}).count() == ships.iter().sum::<usize>() {
+        events.extend(self.game_over_event(state_before));
+        let results = self.log[self.log.len() - shots.len()..].iter().map(|s| s.result).collect();
+        Ok(VolleyOutcome { results, events })
+    }
+
+    /// Rolls back the last shot. Returns `None` if no shot has been fired yet.
+    pub fn undo(&mut self) -> Option<Shot> {
+        let shot = self.log.pop()?;
+        if self.volleys.last() == Some(&self.log.len()) {
+            self.volleys.pop();
+        }
+
+        // Note: Repeated shots did not change the shooting board
+        if self.log.iter().any(|s| s.location == shot.location) {
+            return Some(shot);
+        }
+
+        if shot.result == SquareContent::SunkenShip {
+            // The shot sank the ship -> the other squares of the ship are just hit again
+            if let Some(ship) = self.fleet.ship_at(shot.location) {
+                for i in ship.squares() {
+                    self.shooting_board[i] = SquareContent::HitShip;
+                }
+
+                if self.reveals_water() {
+                    // Note: Revealed squares have not been shot at. Squares next to
+                    //       another sunken ship remain revealed.
+                    let side_length = self.side_length();
+                    for i in ship.squares().flat_map(|i| i.neighbors8(side_length)) {
+                        if self.shooting_board[i] == SquareContent::Water
+                            && !self.log.iter().any(|s| s.location == i)
+                            && !i.neighbors8(side_length).any(|n| self.shooting_board[n] == SquareContent::SunkenShip)
+                        {
+                            self.shooting_board[i] = SquareContent::Unknown;
+                        }
+                    }
+                }
+            }
+        }
+
+        if shot.result.is_ship() {
+            self.fleet.undo_hit(shot.location);
+        }
+
+        // Note: Water next to a sunken ship has been revealed before the shot, so it stays revealed
+        let side_length = self.side_length();
+        let revealed = shot.result == SquareContent::Water
+            && self.reveals_water()
+            && shot.location.neighbors8(side_length).any(|n| self.shooting_board[n] == SquareContent::SunkenShip);
+        if !revealed {
+            self.shooting_board[shot.location] = SquareContent::Unknown;
+        }
+
+        Some(shot)
+    }
+
+    pub fn game_state(&self) -> GameState {
+        // Note: Sinking the last ship with the last allowed shot wins the game
+        if self.all_ships_sunken() {
             return GameState::AllShipsSunken;
         }
 
+        if self.log.len() >= self.rules.max_shots {
+            return GameState::TooManyShots;
+        }
+
         GameState::InProgress
     }
 
+    pub(crate) fn all_ships_sunken(&self) -> bool {
+        self.fleet.all_sunk()
+    }
+
     pub fn log(&self) -> impl Iterator<Item = &Shot> {
         self.log.iter()
     }
 
+    /// Shots grouped by the turn in which they have been fired
+    pub fn volleys(&self) -> impl Iterator<Item = &[Shot]> {
+        let ends = self.volleys.iter().skip(1).copied().chain(std::iter::once(self.log.len()));
+        self.volleys.iter().zip(ends).map(move |(start, end)| &self.log[*start..end])
+    }
+
+    pub(crate) fn shots(&self) -> &[Shot] {
+        &self.log
+    }
+
     pub fn board_buffer(&self) -> *const SquareContent {
         self.shooting_board.board_buffer()
     }
 }
 
+/// Turns the unknown squares around the given ship squares into water
+fn reveal_water(shooting_board: &mut BattleshipBoardContent, ship: impl Iterator<Item = BoardIndex>) -> Vec<BoardIndex> {
+    let side_length = shooting_board.side_length();
+    let mut revealed = Vec::new();
+    for ix in ship.flat_map(|ix| ix.neighbors8(side_length)) {
+        if shooting_board[ix] == SquareContent::Unknown {
+            shooting_board[ix] = SquareContent::Water;
+            revealed.push(ix);
+        }
+    }
+
+    revealed.sort();
+    revealed
+}
+
 impl Default for SinglePlayerGame {
     fn default() -> Self {
         Self::new()
@@ -119,11 +498,21 @@ impl Default for SinglePlayerGame {
 mod tests {
     use std::str::FromStr;
 
+    use rstest::rstest;
+
+    use crate::{Direction, SeededPlacer, ShapePlacement, ShipKind, ShipPlacement, ShipShape};
+
+    const SEED_42_FLEET: &str = "~~~~~~~~~~~S~~~~~~~~~S~S~~~~~~~S~S~~S~~~~~~S~~S~~~~S~S~~~~S~~S~~~~~~S~~S~~~~~~S~~S~~~~~~~~~S~~~~~~~~";
+
     use super::*;
 
+    fn with_ship(start: &str, ship_length: usize, rules: GameRules) -> SinglePlayerGame {
+        SinglePlayerGame::with_fleet(Fleet::new(&[ShipPlacement::new(start.into(), ship_length, Direction::Horizontal)]), rules)
+    }
+
     #[test]
     fn clone() {
-        let mut game = SinglePlayerGame::new_internal(false);
+        let mut game = SinglePlayerGame::without_ships(GameRules::default());
         game.shoot("A1".into());
 
         let clone = game.clone();
@@ -132,15 +521,14 @@ mod tests {
 
     #[test]
     fn shoot_into_water() {
-        let mut game = SinglePlayerGame::new_internal(false);
+        let mut game = SinglePlayerGame::without_ships(GameRules::default());
         assert_eq!(SquareContent::Water, game.shoot("A1".into()));
         assert_eq!(SquareContent::Water, game.shooting_board[BoardIndex::from_str("A1").unwrap()]);
     }
 
     #[test]
     fn shoot_ship() {
-        let mut game = SinglePlayerGame::new_internal(false);
-        game.board[BoardIndex::from("A1")] = SquareContent::Ship;
+        let mut game = with_ship("A1", 2, GameRules::default());
 
         assert_eq!(SquareContent::HitShip, game.shoot("A1".into()));
         assert_eq!(SquareContent::HitShip, game.shooting_board[BoardIndex::from_str("A1").unwrap()]);
@@ -148,49 +536,449 @@ mod tests {
 
     #[test]
     fn sink_ship() {
-        let mut game = SinglePlayerGame::new_internal(false);
-        game.board[BoardIndex::from("A1")] = SquareContent::Ship;
-        game.board[BoardIndex::from("B1")] = SquareContent::Ship;
+        let mut game = with_ship("A1", 2, GameRules::default());
 
         assert_eq!(SquareContent::HitShip, game.shoot("A1".into()));
+        assert!(game.sunken_ship_at("A1".into()).is_none());
         assert_eq!(SquareContent::SunkenShip, game.shoot("B1".into()));
+        assert_eq!(ShipKind::Destroyer, game.sunken_ship_at("A1".into()).unwrap().kind());
         assert_eq!(SquareContent::SunkenShip, game.shooting_board[BoardIndex::from("A1")]);
         assert_eq!(SquareContent::SunkenShip, game.shooting_board[BoardIndex::from("B1")]);
     }
 
     #[test]
     fn get_winner_in_progress() {
-        let mut game = SinglePlayerGame::new_internal(false);
-        game.board[BoardIndex::from("A1")] = SquareContent::HitShip;
+        let mut game = with_ship("A1", 2, GameRules::default());
+        game.shoot("A1".into());
         assert_eq!(GameState::InProgress, game.game_state());
-       
     }
 
     #[test]
     fn get_winner_too_many_shots() {
-        let mut game = SinglePlayerGame::new_internal(false);
-        for _ in 0..=100 {
+        let mut game = SinglePlayerGame::without_ships(GameRules::default());
+        for _ in 0..100 {
             assert_eq!(GameState::InProgress, game.game_state());
-            game.shoot(BoardIndex::from(0));
+            game.shoot(BoardIndex::new());
         }
 
         assert_eq!(GameState::TooManyShots, game.game_state());
     }
 
+    #[test]
+    fn win_with_last_shot() {
+        let mut game = with_ship("A1", 1, GameRules { ship_lengths: vec![1], max_shots: 2, ..Default::default() });
+        game.shoot("J10".into());
+        game.shoot("A1".into());
+        assert_eq!(GameState::AllShipsSunken, game.game_state());
+    }
+
     #[test]
     fn get_winner() {
-        let mut game = SinglePlayerGame::new_internal(false);
-        game.shooting_board[BoardIndex::from("A1")] = SquareContent::SunkenShip;
-        assert_eq!(GameState::AllShipsSunken, game.game_state_internal(&[1]));
+        let mut game = with_ship("A1", 1, GameRules { ship_lengths: vec![1], ..Default::default() });
+        assert_eq!(SquareContent::SunkenShip, game.shoot("A1".into()));
+        assert_eq!(GameState::AllShipsSunken, game.game_state());
+    }
+
+    #[test]
+    fn get_winner_custom_shot_limit() {
+        let mut game = SinglePlayerGame::without_ships(GameRules { max_shots: 60, ..Default::default() });
+        for _ in 0..59 {
+            game.shoot(BoardIndex::new());
+        }
+        assert_eq!(GameState::InProgress, game.game_state());
+
+        game.shoot(BoardIndex::new());
+        assert_eq!(GameState::TooManyShots, game.game_state());
+    }
+
+    #[test]
+    fn with_rules_fleet() {
+        let rules = GameRules { ship_lengths: vec![4, 3, 3, 2, 2, 2, 1, 1, 1, 1], ..Default::default() };
+        let game = SinglePlayerGame::with_rules(rules.clone());
+        assert_eq!(&rules, game.rules());
+        assert_eq!(20, game.board.iter().filter(|s| *s == SquareContent::Ship).count());
+    }
+
+    #[test]
+    fn with_rules_ships_may_touch() {
+        // Note: Without touching ships, this fleet does not fit on a 2x2 board
+        let rules = GameRules {
+            side_length: 2,
+            ship_lengths: vec![2, 2],
+            adjacency: AdjacencyRule::MayTouch,
+            ..Default::default()
+        };
+        let game = SinglePlayerGame::with_rules(rules);
+        assert!(game.board.iter().all(|s| s == SquareContent::Ship));
     }
 
     #[test]
     fn log() {
-        let mut game = SinglePlayerGame::new_internal(false);
+        let mut game = SinglePlayerGame::without_ships(GameRules::default());
         game.shoot("A1".into());
         game.shoot("A2".into());
 
         assert_eq!(2, game.log().count());
-        assert_eq!(BoardIndex::from_str("A1").unwrap(), game.log().nth(0).unwrap().location);
+        assert_eq!(BoardIndex::from_str("A1").unwrap(), game.log().next().unwrap().location);
+    }
+
+    #[test]
+    fn with_side_length() {
+        let mut game = SinglePlayerGame::with_side_length(15);
+        assert_eq!(15, game.side_length());
+        assert_eq!(225, game.shooting_board_str().len());
+
+        game.shoot("O15".into());
+        assert_ne!(SquareContent::Unknown, game.shooting_board()[BoardIndex::from("O15")]);
+    }
+
+    #[test]
+    fn with_small_side_length() {
+        let game = SinglePlayerGame::with_side_length(8);
+        assert_eq!(17, game.board.iter().filter(|s| *s == SquareContent::Ship).count());
+    }
+
+    #[test]
+    fn same_seed_same_fleet() {
+        let game = SinglePlayerGame::with_seed(GameRules::default(), 42);
+        let other = SinglePlayerGame::with_seed(GameRules::default(), 42);
+        assert_eq!(Some(42), game.seed());
+        assert_eq!(game.board, other.board);
+        assert_ne!(game.board, SinglePlayerGame::with_seed(GameRules::default(), 43).board);
+    }
+
+    #[test]
+    fn seeded_fleet_is_stable() {
+        // Note: If this test fails, boards of existing bug reports and saved
+        //       games cannot be reproduced anymore.
+        let game = SinglePlayerGame::with_seed(GameRules::default(), 42);
+        assert_eq!(SEED_42_FLEET, game.board.to_compact_str());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn with_rng() {
+        let game = SinglePlayerGame::with_rng(GameRules::default(), &mut placement_rng(42));
+        assert_eq!(None, game.seed());
+        assert_eq!(SinglePlayerGame::with_seed(GameRules::default(), 42).board, game.board);
+    }
+
+    #[test]
+    fn with_strategy() {
+        let mut strategy = SeededPlacer::new(backtracking_placer, 42);
+        let game = SinglePlayerGame::try_with_strategy(GameRules::default(), &mut strategy).unwrap();
+        assert_eq!(SinglePlayerGame::with_seed(GameRules::default(), 42).board, game.board);
+        assert_eq!(5, game.fleet().ships().len());
+    }
+
+    #[test]
+    fn random_seed() {
+        assert!(SinglePlayerGame::new().seed().is_some());
+    }
+
+    #[test]
+    fn try_with_rules_fleet_does_not_fit() {
+        let rules = GameRules { side_length: 4, ship_lengths: vec![3, 3, 3], ..Default::default() };
+        assert!(SinglePlayerGame::try_with_rules(rules).is_err());
+    }
+
+    #[rstest]
+    #[case(AdjacencyRule::NoTouching)]
+    #[case(AdjacencyRule::MayTouch)]
+    fn try_with_rules_empty_ship(#[case] adjacency: AdjacencyRule) {
+        let rules = GameRules { ship_lengths: vec![3, 0], adjacency, ..Default::default() };
+        assert_eq!(Some(ErrorKind::EmptyShip), SinglePlayerGame::try_with_rules(rules).err().map(|e| e.error_kind()));
+    }
+
+    #[test]
+    fn undo_miss_and_hit() {
+        let mut game = with_ship("B1", 2, GameRules::default());
+        game.shoot("A1".into());
+        game.shoot("B1".into());
+
+        assert_eq!(SquareContent::HitShip, game.undo().unwrap().result);
+        assert_eq!(SquareContent::Unknown, game.shooting_board[BoardIndex::from("B1")]);
+        assert_eq!(SquareContent::Water, game.undo().unwrap().result);
+        assert_eq!(SquareContent::Unknown, game.shooting_board[BoardIndex::from("A1")]);
+        assert!(game.undo().is_none());
+    }
+
+    #[test]
+    fn undo_sinking_shot() {
+        let mut game = with_ship("A1", 2, GameRules { ship_lengths: vec![2], ..Default::default() });
+        game.shoot("A1".into());
+        game.shoot("B1".into());
+        assert_eq!(GameState::AllShipsSunken, game.game_state());
+
+        game.undo();
+        assert_eq!(SquareContent::HitShip, game.shooting_board[BoardIndex::from("A1")]);
+        assert_eq!(SquareContent::Unknown, game.shooting_board[BoardIndex::from("B1")]);
+        assert_eq!(GameState::InProgress, game.game_state());
+        assert_eq!(1, game.fleet().ships()[0].hits());
+    }
+
+    #[test]
+    fn undo_repeated_shot() {
+        let mut game = SinglePlayerGame::without_ships(GameRules::default());
+        game.shoot("A1".into());
+        game.shoot("A1".into());
+
+        game.undo();
+        assert_eq!(SquareContent::Water, game.shooting_board[BoardIndex::from("A1")]);
+        assert_eq!(1, game.log().count());
+    }
+
+    #[test]
+    fn fleet_of_random_game() {
+        let game = SinglePlayerGame::with_seed(GameRules::default(), 42);
+        assert_eq!(5, game.fleet().ships().len());
+        assert_eq!(game.board, game.fleet().to_board(10));
+    }
+
+    #[test]
+    fn fleet_with_touching_ships() {
+        let rules = GameRules { side_length: 2, ship_lengths: vec![2, 2], adjacency: AdjacencyRule::MayTouch, ..Default::default() };
+        let mut game = SinglePlayerGame::with_rules(rules);
+        let first = game.fleet().ships()[0].clone();
+        for ix in first.squares() {
+            game.shoot(ix);
+        }
+
+        assert_eq!(Some(&first.kind()), game.sunken_ship_at(first.shape().origin).map(|s| s.kind()).as_ref());
+        assert_eq!(GameState::InProgress, game.game_state());
+    }
+
+    #[test]
+    fn shoot_with_events() {
+        let mut game = with_ship("A1", 2, GameRules { ship_lengths: vec![2], ..Default::default() });
+        assert_eq!(vec![GameEvent::Miss("C1".into())], game.shoot_with_events("C1".into()));
+        assert_eq!(vec![GameEvent::Hit("A1".into())], game.shoot_with_events("A1".into()));
+        assert_eq!(vec![GameEvent::RepeatShot("A1".into())], game.shoot_with_events("A1".into()));
+        assert_eq!(
+            vec![
+                GameEvent::Hit("B1".into()),
+                GameEvent::Sunk { ship: ShipKind::Destroyer, squares: vec!["A1".into(), "B1".into()] },
+                GameEvent::GameOver(GameState::AllShipsSunken),
+            ],
+            game.shoot_with_events("B1".into())
+        );
+        assert_eq!(SquareContent::SunkenShip, game.log().last().unwrap().result);
+    }
+
+    #[test]
+    fn too_many_shots_event() {
+        let mut game = with_ship("A1", 2, GameRules { max_shots: 2, ..Default::default() });
+        game.shoot("J10".into());
+        assert_eq!(
+            vec![GameEvent::Miss("J9".into()), GameEvent::GameOver(GameState::TooManyShots)],
+            game.shoot_with_events("J9".into())
+        );
+        assert_eq!(vec![GameEvent::RepeatShot("J9".into())], game.shoot_with_events("J9".into()));
+    }
+
+    #[test]
+    fn try_shoot() {
+        let mut game = with_ship("A1", 2, GameRules { ship_lengths: vec![2], ..Default::default() });
+        assert_eq!(
+            Ok(ShotOutcome { result: SquareContent::HitShip, events: vec![GameEvent::Hit("A1".into())] }),
+            game.try_shoot("A1".into())
+        );
+        assert_eq!(Err(ShotError::OutOfBounds(BoardIndex::from_col_row(10, 0))), game.try_shoot(BoardIndex::from_col_row(10, 0)));
+
+        // Note: Repeat shots are counted by default
+        assert_eq!(SquareContent::HitShip, game.try_shoot("A1".into()).unwrap().result);
+        assert_eq!(2, game.log().count());
+
+        assert_eq!(SquareContent::SunkenShip, game.try_shoot("B1".into()).unwrap().result);
+        assert_eq!(Err(ShotError::GameFinished(GameState::AllShipsSunken)), game.try_shoot("C1".into()));
+        assert_eq!(3, game.log().count());
+    }
+
+    #[test]
+    fn try_shoot_rejects_repeat_shots() {
+        let rules = GameRules { max_shots: 2, repeat_shots: RepeatShotRule::Reject, ..Default::default() };
+        let mut game = with_ship("A1", 2, rules);
+        game.try_shoot("J10".into()).unwrap();
+        assert_eq!(Err(ShotError::AlreadyShot("J10".into())), game.try_shoot("J10".into()));
+        assert_eq!(1, game.log().count());
+
+        game.try_shoot("J9".into()).unwrap();
+        assert_eq!(Err(ShotError::GameFinished(GameState::TooManyShots)), game.try_shoot("J8".into()));
+        assert_eq!(2, game.log().count());
+    }
+
+    fn salvo_rules() -> GameRules {
+        GameRules { ship_lengths: vec![2, 2], firing_mode: FiringMode::Salvo, ..Default::default() }
+    }
+
+    fn with_two_ships(rules: GameRules) -> SinglePlayerGame {
+        let placements = [
+            ShipPlacement::new("A1".into(), 2, Direction::Horizontal),
+            ShipPlacement::new("A5".into(), 2, Direction::Horizontal),
+        ];
+        SinglePlayerGame::with_fleet(Fleet::new(&placements), rules)
+    }
+
+    #[test]
+    fn shoot_salvo() {
+        let mut game = with_two_ships(salvo_rules());
+        let outcome = game.shoot_salvo(&["A1".into(), "J10".into()]).unwrap();
+        assert_eq!(vec![SquareContent::HitShip, SquareContent::Water], outcome.results);
+        assert_eq!(vec![GameEvent::Hit("A1".into()), GameEvent::Miss("J10".into())], outcome.events);
+
+        // Note: Volleys are logged as single shots, but can be read as groups
+        game.shoot_salvo(&["B1".into()]).unwrap();
+        assert_eq!(3, game.log().count());
+        assert_eq!(vec![2, 1], game.volleys().map(|v| v.len()).collect::<Vec<_>>());
+
+        // One ship has been sunk -> one shot left
+        assert_eq!(
+            Err(ShotError::InvalidVolleySize { size: 2, allowed: 1 }),
+            game.shoot_salvo(&["A5".into(), "B5".into()])
+        );
+        let outcome = game.shoot_salvo(&["A5".into()]).unwrap();
+        assert_eq!(GameState::InProgress, game.game_state());
+        assert_eq!(vec![GameEvent::Hit("A5".into())], outcome.events);
+    }
+
+    #[test]
+    fn salvo_game_over_after_volley() {
+        let mut game = with_two_ships(salvo_rules());
+        game.shoot_salvo(&["A1".into(), "A5".into()]).unwrap();
+        let outcome = game.shoot_salvo(&["B1".into(), "B5".into()]).unwrap();
+        assert_eq!(Some(&GameEvent::GameOver(GameState::AllShipsSunken)), outcome.events.last());
+        assert_eq!(1, outcome.events.iter().filter(|e| matches!(e, GameEvent::GameOver(_))).count());
+        assert_eq!(Err(ShotError::GameFinished(GameState::AllShipsSunken)), game.shoot_salvo(&["J1".into()]));
+    }
+
+    #[test]
+    fn volley_does_not_exceed_shot_limit() {
+        let mut game = with_two_ships(GameRules { max_shots: 1, ..salvo_rules() });
+        assert_eq!(
+            Err(ShotError::InvalidVolleySize { size: 2, allowed: 1 }),
+            game.shoot_salvo(&["J1".into(), "J2".into()])
+        );
+        assert_eq!(0, game.log().count());
+
+        let mut game = with_two_ships(GameRules { max_shots: 3, ..salvo_rules() });
+        game.shoot_salvo(&["J1".into(), "J2".into()]).unwrap();
+        assert_eq!(
+            Err(ShotError::InvalidVolleySize { size: 2, allowed: 1 }),
+            game.shoot_salvo(&["J3".into(), "J4".into()])
+        );
+        game.shoot_salvo(&["J3".into()]).unwrap();
+        assert_eq!(3, game.log().count());
+        assert_eq!(GameState::TooManyShots, game.game_state());
+        assert_eq!(Err(ShotError::GameFinished(GameState::TooManyShots)), game.shoot_salvo(&["J4".into()]));
+    }
+
+    #[test]
+    fn invalid_volleys_are_not_fired() {
+        let mut game = with_two_ships(salvo_rules());
+        assert_eq!(Err(ShotError::InvalidVolleySize { size: 0, allowed: 2 }), game.shoot_salvo(&[]));
+        assert_eq!(Err(ShotError::DuplicateShot("A1".into())), game.shoot_salvo(&["A1".into(), "A1".into()]));
+        assert_eq!(
+            Err(ShotError::OutOfBounds(BoardIndex::from_col_row(10, 0))),
+            game.shoot_salvo(&["A1".into(), BoardIndex::from_col_row(10, 0)])
+        );
+        assert_eq!(0, game.log().count());
+    }
+
+    #[test]
+    fn salvo_requires_salvo_mode() {
+        let mut game = with_two_ships(GameRules { ship_lengths: vec![2, 2], ..Default::default() });
+        assert_eq!(
+            Err(ShotError::InvalidVolleySize { size: 2, allowed: 1 }),
+            game.shoot_salvo(&["A1".into(), "A5".into()])
+        );
+        assert!(game.shoot_salvo(&["A1".into()]).is_ok());
+    }
+
+    #[test]
+    fn undo_volley() {
+        let mut game = with_two_ships(salvo_rules());
+        game.shoot("J1".into());
+        game.shoot_salvo(&["A1".into(), "J10".into()]).unwrap();
+        game.undo();
+        assert_eq!(vec![1, 1], game.volleys().map(|v| v.len()).collect::<Vec<_>>());
+        game.undo();
+        assert_eq!(1, game.volleys().count());
+    }
+
+    #[test]
+    fn sink_shaped_ship() {
+        // X
+        // XX
+        let shape = ShipShape::new([(0, 0), (0, 1), (1, 1)]).unwrap();
+        let fleet = Fleet::with_shapes(vec![ShapePlacement::new("B2".into(), shape.clone())]);
+        let mut game = SinglePlayerGame::with_fleet(fleet, GameRules { ship_lengths: vec![3], ..Default::default() });
+
+        assert_eq!(SquareContent::HitShip, game.shoot("B2".into()));
+        assert_eq!(SquareContent::Water, game.shoot("C2".into()));
+        assert_eq!(SquareContent::HitShip, game.shoot("C3".into()));
+        assert_eq!(
+            vec![
+                GameEvent::Hit("B3".into()),
+                GameEvent::Sunk { ship: ShipKind::Other(3), squares: shape.squares_at("B2".into()).collect() },
+                GameEvent::GameOver(GameState::AllShipsSunken),
+            ],
+            game.shoot_with_events("B3".into())
+        );
+        assert_eq!(3, game.shooting_board().iter().filter(|s| *s == SquareContent::SunkenShip).count());
+    }
+
+    #[test]
+    fn reveal_water_around_sunken_ship() {
+        let rules = GameRules { ship_lengths: vec![2], reveal_water: true, ..Default::default() };
+        let mut game = with_ship("A1", 2, rules);
+        game.shoot("A1".into());
+        let events = game.shoot_with_events("B1".into());
+
+        let revealed = vec!["C1".into(), "A2".into(), "B2".into(), "C2".into()];
+        assert_eq!(GameEvent::WaterRevealed(revealed), events[2]);
+        assert_eq!(SquareContent::Water, game.shooting_board()[BoardIndex::from("C2")]);
+        assert_eq!(2, game.log().count());
+
+        game.undo();
+        assert!(game.shooting_board().iter().filter(|s| *s != SquareContent::Unknown).eq([SquareContent::HitShip]));
+    }
+
+    #[test]
+    fn undo_keeps_water_next_to_other_sunken_ship() {
+        let rules = GameRules { ship_lengths: vec![1, 1], reveal_water: true, ..Default::default() };
+        let placements = [
+            ShipPlacement::new("A1".into(), 1, Direction::Horizontal),
+            ShipPlacement::new("C1".into(), 1, Direction::Horizontal),
+        ];
+        let mut game = SinglePlayerGame::with_fleet(Fleet::new(&placements), rules);
+        game.shoot("A1".into());
+        game.shoot("B2".into());
+        game.shoot("C1".into());
+        game.undo();
+
+        assert_eq!(SquareContent::Water, game.shooting_board()[BoardIndex::from("B1")]);
+        assert_eq!(SquareContent::Water, game.shooting_board()[BoardIndex::from("B2")]);
+        assert_eq!(SquareContent::Unknown, game.shooting_board()[BoardIndex::from("D1")]);
+    }
+
+    #[test]
+    fn undo_shot_at_revealed_water() {
+        let rules = GameRules { ship_lengths: vec![2], reveal_water: true, ..Default::default() };
+        let mut game = with_ship("A1", 2, rules);
+        game.shoot("A1".into());
+        game.shoot("B1".into());
+        game.shoot("B2".into());
+        game.undo();
+
+        assert_eq!(SquareContent::Water, game.shooting_board()[BoardIndex::from("B2")]);
+        assert_eq!(SquareContent::SunkenShip, game.shooting_board()[BoardIndex::from("B1")]);
+        assert_eq!(2, game.log().count());
+    }
+
+    #[test]
+    fn no_reveal_if_ships_may_touch() {
+        let rules = GameRules { ship_lengths: vec![1], reveal_water: true, adjacency: AdjacencyRule::MayTouch, ..Default::default() };
+        let mut game = with_ship("A1", 1, rules);
+        game.shoot("A1".into());
+        assert_eq!(99, game.shooting_board().iter().filter(|s| *s == SquareContent::Unknown).count());
+    }
+}
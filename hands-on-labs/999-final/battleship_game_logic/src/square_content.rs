@@ -15,21 +15,26 @@
     * `derive` macro: https://doc.rust-lang.org/reference/procedural-macros.html#derive-macros
     * `Default` trait: https://doc.rust-lang.org/std/default/trait.Default.html
     * `From` and `Into` traits: https://doc.rust-lang.org/rust-by-example/conversion/from_into.html
+    * `TryFrom` and `TryInto` traits: https://doc.rust-lang.org/rust-by-example/conversion/try_from_try_into.html
     * `Copy` and `Clone` traits: https://doc.rust-lang.org/std/marker/trait.Copy.html
     * `PartialEq` and `Eq` traits: https://doc.rust-lang.org/std/cmp/trait.PartialEq.html
     * `match` keyword: https://doc.rust-lang.org/rust-by-example/flow_control/match.html
     * `matches` macro: https://doc.rust-lang.org/std/macro.matches.html
-    * `panic` macro: https://doc.rust-lang.org/std/macro.panic.html
     * Unit testing: https://doc.rust-lang.org/rust-by-example/testing/unit_testing.html
 */
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+use std::{convert::TryFrom, fmt};
+
+// Note: `Default` can be derived for enums by marking the default variant
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum SquareContent {
     Water,
     Ship,
     HitShip,
     SunkenShip,
+    #[default]
     Unknown,
 }
 
@@ -39,21 +44,36 @@ impl SquareContent {
     }
 }
 
-impl Default for SquareContent {
-    fn default() -> Self {
-        SquareContent::Unknown
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SquareContentError {
+    InvalidByte(u8),
+    InvalidChar(char),
+}
+
+impl fmt::Display for SquareContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SquareContentError::InvalidByte(v) => write!(f, "Cannot convert {} to square content", v),
+            SquareContentError::InvalidChar(c) => write!(f, "Cannot convert {:?} to square content", c),
+        }
     }
 }
 
-impl From<u8> for SquareContent {
-    fn from(value: u8) -> Self {
+impl std::error::Error for SquareContentError {}
+
+// Note: We cannot implement `From<u8>` and `TryFrom<u8>` at the same time. The standard
+//       library implements `TryFrom` for all types implementing `From`.
+impl TryFrom<u8> for SquareContent {
+    type Error = SquareContentError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            0 => SquareContent::Water,
-            1 => SquareContent::Ship,
-            2 => SquareContent::HitShip,
-            3 => SquareContent::SunkenShip,
-            4 => SquareContent::Unknown,
-            v => panic!("Cannot convert {} to square content", v),
+            0 => Ok(SquareContent::Water),
+            1 => Ok(SquareContent::Ship),
+            2 => Ok(SquareContent::HitShip),
+            3 => Ok(SquareContent::SunkenShip),
+            4 => Ok(SquareContent::Unknown),
+            v => Err(SquareContentError::InvalidByte(v)),
         }
     }
 }
@@ -84,15 +104,17 @@ impl From<SquareContent> for char {
     }
 }
 
-impl From<char> for SquareContent {
-    fn from(value: char) -> Self {
+impl TryFrom<char> for SquareContent {
+    type Error = SquareContentError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '~' => SquareContent::Water,
-            'S' => SquareContent::Ship,
-            'h' => SquareContent::HitShip,
-            'X' => SquareContent::SunkenShip,
-            ' ' => SquareContent::Unknown,
-            _ => panic!("Invalid character")
+            '~' => Ok(SquareContent::Water),
+            'S' => Ok(SquareContent::Ship),
+            'h' => Ok(SquareContent::HitShip),
+            'X' => Ok(SquareContent::SunkenShip),
+            ' ' => Ok(SquareContent::Unknown),
+            c => Err(SquareContentError::InvalidChar(c)),
         }
     }
 }
@@ -108,16 +130,16 @@ mod tests {
     }
 
     #[test]
-    fn from() {
-        let c: SquareContent = SquareContent::from(1);
+    fn try_from() {
+        let c = SquareContent::try_from(1).unwrap();
         assert_eq!(SquareContent::Ship, c);
     }
 
     #[test]
-    #[should_panic(expected = "99")]
-    #[allow(unused_must_use)]
-    fn from_fails() {
-        SquareContent::from(99);
+    fn try_from_fails() {
+        let err = SquareContent::try_from(99).unwrap_err();
+        assert_eq!(SquareContentError::InvalidByte(99), err);
+        assert!(err.to_string().contains("99"));
     }
 
     #[test]
@@ -139,6 +161,15 @@ mod tests {
 
     #[test]
     fn from_char() {
-        assert_eq!(SquareContent::Ship, 'S'.into());
+        assert_eq!(Ok(SquareContent::Ship), 'S'.try_into());
+        assert_eq!(Err(SquareContentError::InvalidChar('?')), SquareContent::try_from('?'));
+    }
+
+    #[test]
+    fn round_trip() {
+        for c in [SquareContent::Water, SquareContent::Ship, SquareContent::HitShip, SquareContent::SunkenShip, SquareContent::Unknown] {
+            assert_eq!(Ok(c), SquareContent::try_from(u8::from(c)));
+            assert_eq!(Ok(c), SquareContent::try_from(char::from(c)));
+        }
     }
 }
@@ -0,0 +1,274 @@
+use std::iter::FromIterator;
+
+use crate::{
+    AdjacencyRule, BattleshipBoardContent, BoardDimensions, BoardIndex, GameState, ShipKind, SinglePlayerGame,
+    SquareContent,
+};
+
+/*
+    Learnings in this module:
+
+    * Deriving data from a log of events
+    * Implementing `FromIterator` and `Extend` to support `collect`
+    * Converting between integer and floating point types
+
+    Recommended readings for this module:
+
+    * `FromIterator` trait: https://doc.rust-lang.org/std/iter/trait.FromIterator.html
+    * `Extend` trait: https://doc.rust-lang.org/std/iter/trait.Extend.html
+    * Numeric casts: https://doc.rust-lang.org/reference/expressions/operator-expr.html#numeric-cast
+*/
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ShipStatistics {
+    pub kind: ShipKind,
+    pub ship_length: usize,
+    /// Position of the first shot that hit the ship in the log
+    pub first_hit: usize,
+    /// Position of the shot that sank the ship in the log
+    pub sunk: usize,
+}
+
+impl ShipStatistics {
+    /// Number of shots from the first hit up to and including the sinking shot
+    pub fn shots_to_sink(&self) -> usize {
+        self.sunk - self.first_hit + 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameStatistics {
+    pub game_state: GameState,
+    pub shots: usize,
+    /// Shots that hit a ship square for the first time
+    pub hits: usize,
+    pub misses: usize,
+    pub longest_miss_streak: usize,
+    pub repeat_shots: usize,
+    /// Shots next to sunken ships although ships must not touch each other
+    pub shots_next_to_sunken_ships: usize,
+    /// Sunken ships in the order in which they have been sunk
+    pub ships: Vec<ShipStatistics>,
+}
+
+impl GameStatistics {
+    pub fn from_game(game: &SinglePlayerGame) -> GameStatistics {
+        let side_length = game.side_length();
+        let shots = game.shots();
+        let mut stats = GameStatistics {
+            game_state: game.game_state(),
+            shots: shots.len(),
+            hits: 0,
+            misses: 0,
+            longest_miss_streak: 0,
+            repeat_shots: 0,
+            shots_next_to_sunken_ships: 0,
+            ships: Vec::new(),
+        };
+
+        let mut miss_streak = 0;
+        let mut before = BattleshipBoardContent::new_with_side_length(side_length);
+        for ((ix, shot), after) in shots.iter().enumerate().zip(game.shooting_board_states()) {
+            if shot.result == SquareContent::Water {
+                stats.misses += 1;
+                miss_streak += 1;
+                stats.longest_miss_streak = stats.longest_miss_streak.max(miss_streak);
+            } else {
+                miss_streak = 0;
+            }
+
+            if before[shot.location] != SquareContent::Unknown {
+                stats.repeat_shots += 1;
+            } else {
+                if shot.result.is_ship() {
+                    stats.hits += 1;
+                }
+
+                if game.rules().adjacency == AdjacencyRule::NoTouching && next_to_sunken_ship(&before, shot.location) {
+                    stats.shots_next_to_sunken_ships += 1;
+                }
+
+                if shot.result == SquareContent::SunkenShip {
+                    if let Some(ship) = game.fleet().ship_at(shot.location) {
+                        let first_hit = shots.iter().position(|s| ship.contains(s.location)).unwrap_or(ix);
+                        stats.ships.push(ShipStatistics { kind: ship.kind(), ship_length: ship.length(), first_hit, sunk: ix });
+                    }
+                }
+            }
+
+            before = after;
+        }
+
+        stats
+    }
+
+    /// Ratio of hits to shots (0.0 if no shot has been fired)
+    pub fn accuracy(&self) -> f32 {
+        ratio(self.hits, self.shots)
+    }
+
+    pub fn wasted_shots(&self) -> usize {
+        self.repeat_shots + self.shots_next_to_sunken_ships
+    }
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f32 {
+    match denominator {
+        0 => 0.0,
+        d => numerator as f32 / d as f32,
+    }
+}
+
+fn next_to_sunken_ship(shooting_board: &BattleshipBoardContent, ix: BoardIndex) -> bool {
+    std::iter::once(ix)
+        .chain(ix.neighbors8(shooting_board.side_length()))
+        .any(|neighbor| shooting_board[neighbor] == SquareContent::SunkenShip)
+}
+
+/// Statistics summed up over many games (e.g. all games of a player)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AggregatedStatistics {
+    pub games: usize,
+    pub games_won: usize,
+    pub shots: usize,
+    pub hits: usize,
+    pub misses: usize,
+    pub longest_miss_streak: usize,
+    pub wasted_shots: usize,
+    pub sunken_ships: usize,
+    pub shots_to_sink: usize,
+}
+
+impl AggregatedStatistics {
+    pub fn new() -> AggregatedStatistics {
+        Default::default()
+    }
+
+    pub fn add(&mut self, game: &GameStatistics) {
+        self.games += 1;
+        if game.game_state == GameState::AllShipsSunken {
+            self.games_won += 1;
+        }
+
+        self.shots += game.shots;
+        self.hits += game.hits;
+        self.misses += game.misses;
+        self.longest_miss_streak = self.longest_miss_streak.max(game.longest_miss_streak);
+        self.wasted_shots += game.wasted_shots();
+        self.sunken_ships += game.ships.len();
+        self.shots_to_sink += game.ships.iter().map(ShipStatistics::shots_to_sink).sum::<usize>();
+    }
+
+    pub fn accuracy(&self) -> f32 {
+        ratio(self.hits, self.shots)
+    }
+
+    pub fn average_shots(&self) -> f32 {
+        ratio(self.shots, self.games)
+    }
+
+    pub fn average_shots_to_sink(&self) -> f32 {
+        ratio(self.shots_to_sink, self.sunken_ships)
+    }
+}
+
+impl<'a> Extend<&'a GameStatistics> for AggregatedStatistics {
+    fn extend<I: IntoIterator<Item = &'a GameStatistics>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|game| self.add(game));
+    }
+}
+
+impl<'a> FromIterator<&'a GameStatistics> for AggregatedStatistics {
+    fn from_iter<I: IntoIterator<Item = &'a GameStatistics>>(iter: I) -> Self {
+        let mut result = AggregatedStatistics::new();
+        result.extend(iter);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Direction, GameRules, ShipSetter};
+
+    use super::*;
+
+    fn get_game(shots: &[&str]) -> SinglePlayerGame {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("A1".into(), 3, Direction::Horizontal).unwrap();
+        board.try_place_ship("E5".into(), 2, Direction::Vertical).unwrap();
+        let rules = GameRules { ship_lengths: vec![3, 2], ..Default::default() };
+        SinglePlayerGame::replay(board, rules, shots.iter().map(|s| BoardIndex::from(*s)))
+    }
+
+    #[test]
+    fn empty_game() {
+        let stats = GameStatistics::from_game(&get_game(&[]));
+        assert_eq!(0, stats.shots);
+        assert_eq!(0.0, stats.accuracy());
+        assert_eq!(GameState::InProgress, stats.game_state);
+    }
+
+    #[test]
+    fn accuracy_and_miss_streak() {
+        let stats = GameStatistics::from_game(&get_game(&["J1", "J2", "J3", "A1", "H8", "B1"]));
+        assert_eq!(6, stats.shots);
+        assert_eq!(2, stats.hits);
+        assert_eq!(4, stats.misses);
+        assert_eq!(3, stats.longest_miss_streak);
+        assert!((stats.accuracy() - 2.0 / 6.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn shots_to_sink() {
+        let stats = GameStatistics::from_game(&get_game(&["B1", "J1", "A1", "E5", "C1", "E6"]));
+        assert_eq!(GameState::AllShipsSunken, stats.game_state);
+        assert_eq!(
+            vec![
+                ShipStatistics { kind: ShipKind::Cruiser, ship_length: 3, first_hit: 0, sunk: 4 },
+                ShipStatistics { kind: ShipKind::Destroyer, ship_length: 2, first_hit: 3, sunk: 5 },
+            ],
+            stats.ships
+        );
+        assert_eq!(5, stats.ships[0].shots_to_sink());
+        assert_eq!(3, stats.ships[1].shots_to_sink());
+    }
+
+    #[test]
+    fn wasted_shots() {
+        // D1 and B2 are next to the sunken ship, A1 is shot twice
+        let stats = GameStatistics::from_game(&get_game(&["A1", "B1", "C1", "D1", "A1", "B2", "J10"]));
+        assert_eq!(1, stats.repeat_shots);
+        assert_eq!(2, stats.shots_next_to_sunken_ships);
+        assert_eq!(3, stats.wasted_shots());
+        assert_eq!(3, stats.hits);
+    }
+
+    #[test]
+    fn ships_may_touch() {
+        let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        board.try_place_ship("A1".into(), 2, Direction::Horizontal).unwrap();
+        let rules = GameRules { ship_lengths: vec![2], adjacency: AdjacencyRule::MayTouch, ..Default::default() };
+        let game = SinglePlayerGame::replay(board, rules, ["A1".into(), "B1".into(), "C1".into()]);
+        assert_eq!(0, GameStatistics::from_game(&game).wasted_shots());
+    }
+
+    #[test]
+    fn aggregate() {
+        let games = [
+            get_game(&["B1", "J1", "A1", "E5", "C1", "E6"]),
+            get_game(&["J1", "J2", "J3", "A1", "A1"]),
+        ];
+        let stats = games.iter().map(GameStatistics::from_game).collect::<Vec<_>>();
+        let aggregated = stats.iter().collect::<AggregatedStatistics>();
+
+        assert_eq!(2, aggregated.games);
+        assert_eq!(1, aggregated.games_won);
+        assert_eq!(11, aggregated.shots);
+        assert_eq!(6, aggregated.hits);
+        assert_eq!(3, aggregated.longest_miss_streak);
+        assert_eq!(1, aggregated.wasted_shots);
+        assert_eq!(2, aggregated.sunken_ships);
+        assert!((aggregated.average_shots() - 5.5).abs() < f32::EPSILON);
+        assert!((aggregated.average_shots_to_sink() - 4.0).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{BattleshipBoardContent, BoardDimensions, BoardIndex, GameEvent, GameRules, GameState, Shot, ShotError, SinglePlayerGame, SquareContent, VolleyOutcome};
+
+/*
+    Learnings in this module:
+
+    * Composition (reuse a type by embedding it)
+    * Encapsulation with private fields and view types
+    * Returning `Result` for operations that can fail
+    * Implementing `std::error::Error`
+
+    Recommended readings for this module:
+
+    * Encapsulation: https://doc.rust-lang.org/book/ch17-01-what-is-oo.html#encapsulation-that-hides-implementation-details
+    * `Result`: https://doc.rust-lang.org/std/result/index.html
+    * `Error` trait: https://doc.rust-lang.org/std/error/trait.Error.html
+*/
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Player {
+    One,
+    Two,
+}
+
+impl Player {
+    pub fn opponent(&self) -> Player {
+        match self {
+            Player::One => Player::Two,
+            Player::Two => Player::One,
+        }
+    }
+
+    fn ix(&self) -> usize {
+        match self {
+            Player::One => 0,
+            Player::Two => 1,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TurnError {
+    NotYourTurn,
+    GameFinished,
+    /// The shot or volley is not allowed by the rules
+    InvalidShot(ShotError),
+}
+
+impl std::fmt::Display for TurnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TurnError::NotYourTurn => write!(f, "It is the other player's turn"),
+            TurnError::GameFinished => write!(f, "The game is already finished"),
+            TurnError::InvalidShot(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TurnError {}
+
+#[derive(Debug, Clone)]
+pub struct TwoPlayerGame {
+    // Note: Every player shoots at a separate single player game. Its board
+    //       contains the fleet of the opponent.
+    targets: [SinglePlayerGame; 2],
+    current_player: Player,
+}
+
+impl TwoPlayerGame {
+    pub fn new() -> TwoPlayerGame {
+        TwoPlayerGame::with_rules(GameRules::default())
+    }
+
+    pub fn with_rules(rules: GameRules) -> TwoPlayerGame {
+        TwoPlayerGame {
+            targets: [SinglePlayerGame::with_rules(rules.clone()), SinglePlayerGame::with_rules(rules)],
+            current_player: Player::One,
+        }
+    }
+
+    // Note: The fleets are expected to follow the given rules. Panics if the side
+    //       lengths of the boards do not match the rules.
+    pub fn with_fleets(
+        fleet_one: BattleshipBoardContent,
+        fleet_two: BattleshipBoardContent,
+        rules: GameRules,
+    ) -> TwoPlayerGame {
+        TwoPlayerGame {
+            targets: [SinglePlayerGame::with_board(fleet_two, rules.clone()), SinglePlayerGame::with_board(fleet_one, rules)],
+            current_player: Player::One,
+        }
+    }
+
+    pub fn current_player(&self) -> Player {
+        self.current_player
+    }
+
+    pub fn rules(&self) -> &GameRules {
+        self.targets[0].rules()
+    }
+
+    fn check_turn(&self, player: Player) -> Result<(), TurnError> {
+        if self.game_state() != GameState::InProgress {
+            return Err(TurnError::GameFinished);
+        }
+
+        if player != self.current_player {
+            return Err(TurnError::NotYourTurn);
+        }
+
+        Ok(())
+    }
+
+    // Note: A player who has fired all shots is skipped. Otherwise, the game would wait
+    //       for a shot that can never be fired.
+    fn end_turn(&mut self, player: Player) {
+        let opponent = player.opponent();
+        if self.targets[opponent.ix()].log().count() < self.rules().max_shots {
+            self.current_player = opponent;
+        }
+    }
+
+    pub fn shoot(&mut self, player: Player, ix: BoardIndex) -> Result<SquareContent, TurnError> {
+        self.check_turn(player)?;
+        self.targets[player.ix()].check_square(ix).map_err(TurnError::InvalidShot)?;
+        let result = self.targets[player.ix()].shoot(ix);
+        self.end_turn(player);
+        Ok(result)
+    }
+
+    /// Shoots like `shoot` and describes what happened
+    pub fn shoot_with_events(&mut self, player: Player, ix: BoardIndex) -> Result<Vec<GameEvent>, TurnError> {
+        self.check_turn(player)?;
+        self.targets[player.ix()].check_square(ix).map_err(TurnError::InvalidShot)?;
+        let mut events = self.targets[player.ix()].shoot_with_events(ix);
+        self.end_turn(player);
+        self.replace_game_over_event(&mut events);
+        Ok(events)
+    }
+
+    /// Fires a volley. In salvo mode, players fire one shot per ship of their own fleet that is still afloat.
+    pub fn shoot_salvo(&mut self, player: Player, shots: &[BoardIndex]) -> Result<VolleyOutcome, TurnError> {
+        self.check_turn(player)?;
+        let own_fleet = self.targets[player.opponent().ix()].fleet();
+        let ships_afloat = own_fleet.ships().iter().filter(|s| !s.is_sunk()).count();
+
+        let mut outcome = self.targets[player.ix()].fire_volley(shots, ships_afloat).map_err(TurnError::InvalidShot)?;
+        self.end_turn(player);
+        self.replace_game_over_event(&mut outcome.events);
+        Ok(outcome)
+    }
+
+    // Note: The single player game does not know about the opponent. Therefore,
+    //       we replace its game over event with the state of the two-player game.
+    fn replace_game_over_event(&self, events: &mut Vec<GameEvent>) {
+        events.retain(|e| !matches!(e, GameEvent::GameOver(_)));
+        let state = self.game_state();
+        if state != GameState::InProgress {
+            events.push(GameEvent::GameOver(state));
+        }
+    }
+
+    pub fn game_state(&self) -> GameState {
+        // Note: Players take turns, therefore only one of them can have sunk
+        //       all ships of the opponent.
+        if self.targets[Player::One.ix()].all_ships_sunken() {
+            return GameState::PlayerOneWon;
+        }
+
+        if self.targets[Player::Two.ix()].all_ships_sunken() {
+            return GameState::PlayerTwoWon;
+        }
+
+        let max_shots = self.rules().max_shots;
+        if self.targets.iter().all(|t| t.log().count() >= max_shots) {
+            return GameState::TooManyShots;
+        }
+
+        GameState::InProgress
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self.game_state() {
+            GameState::PlayerOneWon => Some(Player::One),
+            GameState::PlayerTwoWon => Some(Player::Two),
+            _ => None,
+        }
+    }
+
+    pub fn view(&self, player: Player) -> PlayerView<'_> {
+        PlayerView { game: self, player }
+    }
+}
+
+impl Default for TwoPlayerGame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Note: A view only offers what the player is allowed to see. There is
+//       no way to get to the fleet of the opponent through it.
+pub struct PlayerView<'a> {
+    game: &'a TwoPlayerGame,
+    player: Player,
+}
+
+impl<'a> PlayerView<'a> {
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn is_my_turn(&self) -> bool {
+        self.game.current_player == self.player
+    }
+
+    /// Squares of the opponent's board as far as they are known to the player
+    pub fn shooting_board(&self) -> &'a BattleshipBoardContent {
+        self.game.targets[self.player.ix()].shooting_board()
+    }
+
+    /// The player's fleet including the shots the opponent fired at it
+    pub fn own_board(&self) -> BattleshipBoardContent {
+        let target = &self.game.targets[self.player.opponent().ix()];
+        let fleet = target.board();
+        let shots = target.shooting_board();
+
+        let mut result = BattleshipBoardContent::new_with_side_length(fleet.side_length());
+        for ix in (0..result.size()).map(|ix| BoardIndex::from_index(ix, fleet.side_length())) {
+            result[ix] = match shots[ix] {
+                SquareContent::Unknown => fleet[ix],
+                shot => shot,
+            };
+        }
+
+        result
+    }
+
+    pub fn log(&self) -> impl Iterator<Item = &'a Shot> {
+        self.game.targets[self.player.ix()].log()
+    }
+
+    pub fn opponent_log(&self) -> impl Iterator<Item = &'a Shot> {
+        self.game.targets[self.player.opponent().ix()].log()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Direction, FiringMode, RepeatShotRule, ShipSetter};
+
+    use super::*;
+
+    fn get_game() -> TwoPlayerGame {
+        get_game_with_rules(GameRules { ship_lengths: vec![2], ..Default::default() })
+    }
+
+    fn get_game_with_rules(rules: GameRules) -> TwoPlayerGame {
+        let mut fleet_one = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        fleet_one.try_place_ship("A1".into(), 2, Direction::Horizontal).unwrap();
+        let mut fleet_two = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        fleet_two.try_place_ship("J9".into(), 2, Direction::Vertical).unwrap();
+
+        TwoPlayerGame::with_fleets(fleet_one, fleet_two, rules)
+    }
+
+    #[test]
+    fn new() {
+        let game = TwoPlayerGame::new();
+        assert_eq!(Player::One, game.current_player());
+        assert_eq!(GameState::InProgress, game.game_state());
+        assert_eq!(17, game.view(Player::One).own_board().iter().filter(|s| s.is_ship()).count());
+        assert_eq!(17, game.view(Player::Two).own_board().iter().filter(|s| s.is_ship()).count());
+    }
+
+    #[test]
+    fn players_take_turns() {
+        let mut game = get_game();
+        assert_eq!(Ok(SquareContent::Water), game.shoot(Player::One, "A1".into()));
+        assert_eq!(Player::Two, game.current_player());
+        assert_eq!(Ok(SquareContent::HitShip), game.shoot(Player::Two, "A1".into()));
+        assert_eq!(Player::One, game.current_player());
+    }
+
+    #[test]
+    fn reject_shot_from_wrong_player() {
+        let mut game = get_game();
+        assert_eq!(Err(TurnError::NotYourTurn), game.shoot(Player::Two, "A1".into()));
+
+        game.shoot(Player::One, "A1".into()).unwrap();
+        assert_eq!(Err(TurnError::NotYourTurn), game.shoot(Player::One, "A2".into()));
+        assert_eq!(1, game.view(Player::One).log().count());
+    }
+
+    #[test]
+    fn reject_shot_out_of_bounds() {
+        let mut game = get_game();
+        let expected = Err(TurnError::InvalidShot(ShotError::OutOfBounds("K1".into())));
+        assert_eq!(expected, game.shoot(Player::One, "K1".into()));
+        assert_eq!(expected.map(|_| Vec::new()), game.shoot_with_events(Player::One, "K1".into()));
+        assert_eq!(Player::One, game.current_player());
+        assert_eq!(0, game.view(Player::One).log().count());
+    }
+
+    #[test]
+    fn reject_repeat_shot() {
+        let rules = GameRules { ship_lengths: vec![2], repeat_shots: RepeatShotRule::Reject, ..Default::default() };
+        let mut game = get_game_with_rules(rules);
+        game.shoot(Player::One, "E5".into()).unwrap();
+        game.shoot(Player::Two, "E5".into()).unwrap();
+
+        let expected = Err(TurnError::InvalidShot(ShotError::AlreadyShot("E5".into())));
+        assert_eq!(expected, game.shoot(Player::One, "E5".into()));
+        assert_eq!(expected.map(|_| Vec::new()), game.shoot_with_events(Player::One, "E5".into()));
+        assert_eq!(Player::One, game.current_player());
+        assert_eq!(1, game.view(Player::One).log().count());
+    }
+
+    #[test]
+    fn winner() {
+        let mut game = get_game();
+        game.shoot(Player::One, "J9".into()).unwrap();
+        game.shoot(Player::Two, "A1".into()).unwrap();
+        assert_eq!(Ok(SquareContent::SunkenShip), game.shoot(Player::One, "J10".into()));
+
+        assert_eq!(GameState::PlayerOneWon, game.game_state());
+        assert_eq!(Some(Player::One), game.winner());
+        assert_eq!(Err(TurnError::GameFinished), game.shoot(Player::Two, "B1".into()));
+    }
+
+    #[test]
+    fn too_many_shots() {
+        let mut game = get_game();
+        for _ in 0..game.rules().max_shots {
+            game.shoot(Player::One, "E5".into()).unwrap();
+            assert_eq!(GameState::InProgress, game.game_state());
+            game.shoot(Player::Two, "E5".into()).unwrap();
+        }
+
+        assert_eq!(GameState::TooManyShots, game.game_state());
+        assert_eq!(None, game.winner());
+    }
+
+    #[test]
+    fn views_are_separated() {
+        let mut game = get_game();
+        game.shoot(Player::One, "J9".into()).unwrap();
+        game.shoot(Player::Two, "A1".into()).unwrap();
+
+        let view = game.view(Player::One);
+        assert!(view.is_my_turn());
+        assert_eq!(SquareContent::HitShip, view.shooting_board()[BoardIndex::from("J9")]);
+        assert_eq!(SquareContent::Unknown, view.shooting_board()[BoardIndex::from("J10")]);
+        assert_eq!(SquareContent::HitShip, view.own_board()[BoardIndex::from("A1")]);
+        assert_eq!(SquareContent::Ship, view.own_board()[BoardIndex::from("B1")]);
+        assert_eq!(SquareContent::Water, view.own_board()[BoardIndex::from("J10")]);
+        assert_eq!(BoardIndex::from("A1"), view.opponent_log().next().unwrap().location);
+
+        let view = game.view(Player::Two);
+        assert!(!view.is_my_turn());
+        assert_eq!(SquareContent::HitShip, view.shooting_board()[BoardIndex::from("A1")]);
+        assert_eq!(SquareContent::Ship, view.own_board()[BoardIndex::from("J10")]);
+    }
+
+    #[test]
+    fn shoot_with_events() {
+        let mut game = get_game();
+        assert_eq!(Ok(vec![GameEvent::Hit("J9".into())]), game.shoot_with_events(Player::One, "J9".into()));
+        assert_eq!(Err(TurnError::NotYourTurn), game.shoot_with_events(Player::One, "J10".into()));
+        assert_eq!(Ok(vec![GameEvent::Miss("J9".into())]), game.shoot_with_events(Player::Two, "J9".into()));
+
+        let events = game.shoot_with_events(Player::One, "J10".into()).unwrap();
+        assert_eq!(Some(&GameEvent::GameOver(GameState::PlayerOneWon)), events.last());
+        assert_eq!(3, events.len());
+    }
+
+    #[test]
+    fn shoot_salvo() {
+        let rules = GameRules { ship_lengths: vec![2, 2], firing_mode: FiringMode::Salvo, ..Default::default() };
+        let mut fleet_one = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        fleet_one.try_place_ship("A1".into(), 2, Direction::Horizontal).unwrap();
+        fleet_one.try_place_ship("A5".into(), 2, Direction::Horizontal).unwrap();
+        let mut fleet_two = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        fleet_two.try_place_ship("J1".into(), 2, Direction::Vertical).unwrap();
+        fleet_two.try_place_ship("J9".into(), 2, Direction::Vertical).unwrap();
+        let mut game = TwoPlayerGame::with_fleets(fleet_one, fleet_two, rules);
+
+        game.shoot_salvo(Player::One, &["J1".into(), "J2".into()]).unwrap();
+
+        // Player two lost a ship -> one shot left
+        assert_eq!(
+            Err(TurnError::InvalidShot(ShotError::InvalidVolleySize { size: 2, allowed: 1 })),
+            game.shoot_salvo(Player::Two, &["A1".into(), "B1".into()])
+        );
+        game.shoot_salvo(Player::Two, &["A1".into()]).unwrap();
+
+        let outcome = game.shoot_salvo(Player::One, &["J9".into(), "J10".into()]).unwrap();
+        assert_eq!(Some(&GameEvent::GameOver(GameState::PlayerOneWon)), outcome.events.last());
+    }
+
+    #[test]
+    fn salvo_up_to_shot_limit() {
+        let rules = GameRules { ship_lengths: vec![1, 1, 1], max_shots: 4, firing_mode: FiringMode::Salvo, ..Default::default() };
+        let mut fleet_one = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        let mut fleet_two = BattleshipBoardContent::new_initialized(SquareContent::Water);
+        for ix in ["A1", "C1", "E1"] {
+            fleet_one.try_place_ship(ix.into(), 1, Direction::Horizontal).unwrap();
+            fleet_two.try_place_ship(ix.into(), 1, Direction::Horizontal).unwrap();
+        }
+        let mut game = TwoPlayerGame::with_fleets(fleet_one, fleet_two, rules);
+
+        game.shoot_salvo(Player::One, &["J1".into(), "J2".into(), "J3".into()]).unwrap();
+        game.shoot_salvo(Player::Two, &["J1".into()]).unwrap();
+
+        // Player one has one shot left
+        assert_eq!(
+            Err(TurnError::InvalidShot(ShotError::InvalidVolleySize { size: 3, allowed: 1 })),
+            game.shoot_salvo(Player::One, &["J4".into(), "J5".into(), "J6".into()])
+        );
+        game.shoot_salvo(Player::One, &["J4".into()]).unwrap();
+        assert_eq!(GameState::InProgress, game.game_state());
+
+        // Player one is out of shots, so player two keeps shooting
+        game.shoot_salvo(Player::Two, &["J2".into()]).unwrap();
+        assert_eq!(Player::Two, game.current_player());
+        assert_eq!(Err(TurnError::NotYourTurn), game.shoot_salvo(Player::One, &["J5".into()]));
+        game.shoot_salvo(Player::Two, &["J3".into(), "J4".into()]).unwrap();
+
+        assert_eq!(GameState::TooManyShots, game.game_state());
+        assert_eq!(Err(TurnError::GameFinished), game.shoot_salvo(Player::One, &["J5".into()]));
+        assert_eq!(Err(TurnError::GameFinished), game.shoot_salvo(Player::Two, &["J5".into()]));
+        assert_eq!(Err(TurnError::GameFinished), game.shoot(Player::Two, "J5".into()));
+    }
+}
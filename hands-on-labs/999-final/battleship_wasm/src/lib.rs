@@ -2,7 +2,7 @@ mod utils;
 
 use std::str::FromStr;
 
-use battleship_game_logic::{BoardIndex, GameState, SinglePlayerGame, SquareContent};
+use battleship_game_logic::{BoardIndex, GameEvent, GameRules, GameState, SinglePlayerGame, SquareContent};
 use wasm_bindgen::prelude::*;
 use serde::Serialize;
 
@@ -41,6 +41,8 @@ pub enum GameStateJS {
     InProgress,
     AllShipsSunken,
     TooManyShots,
+    PlayerOneWon,
+    PlayerTwoWon,
 }
 
 #[wasm_bindgen]
@@ -49,6 +51,35 @@ impl BattleshipGame {
         BattleshipGame { game: SinglePlayerGame::new() }
     }
 
+    pub fn with_side_length(side_length: usize) -> BattleshipGame {
+        BattleshipGame { game: SinglePlayerGame::with_side_length(side_length) }
+    }
+
+    // Note: `u64` is represented as `BigInt` in JavaScript
+    pub fn with_seed(seed: u64) -> BattleshipGame {
+        BattleshipGame { game: SinglePlayerGame::with_seed(GameRules::default(), seed) }
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.game.seed()
+    }
+
+    pub fn side_length(&self) -> usize {
+        self.game.side_length()
+    }
+
+    /// Saved game that can be stored by the client and passed to `load` later
+    pub fn save(&self) -> Result<JsValue, JsValue> {
+        // Note: Seeds are 64 bit numbers. They do not fit into JavaScript numbers
+        //       without losing precision, so we use `BigInt` for them.
+        let serializer = serde_wasm_bindgen::Serializer::new().serialize_large_number_types_as_bigints(true);
+        Ok(self.game.serialize(&serializer)?)
+    }
+
+    pub fn load(saved: JsValue) -> Result<BattleshipGame, JsValue> {
+        Ok(BattleshipGame { game: serde_wasm_bindgen::from_value(saved)? })
+    }
+
     pub fn board_content(&self) -> *const SquareContent {
         self.game.board_buffer()
     }
@@ -60,11 +91,12 @@ impl BattleshipGame {
                 struct Result {
                     shot_result: SquareContentJS,
                     game_state: GameStateJS,
+                    events: Vec<GameEvent>,
                 }
 
-                let shot_result = self.game.shoot(location);
-                Ok(JsValue::from_serde(&Result {
-                    shot_result: match shot_result {
+                let outcome = self.game.try_shoot(location).map_err(|e| JsValue::from(e.to_string()))?;
+                Ok(serde_wasm_bindgen::to_value(&Result {
+                    shot_result: match outcome.result {
                         SquareContent::Water => SquareContentJS::Water,
                         SquareContent::HitShip => SquareContentJS::HitShip,
                         SquareContent::SunkenShip => SquareContentJS::SunkenShip,
@@ -73,8 +105,12 @@ impl BattleshipGame {
                     game_state: match self.game.game_state() {
                         GameState::InProgress => GameStateJS::InProgress,
                         GameState::AllShipsSunken => GameStateJS::AllShipsSunken,
-                        GameState::TooManyShots => GameStateJS::TooManyShots
-                    }
+                        GameState::TooManyShots => GameStateJS::TooManyShots,
+                        // Note: No wildcard arm, new game states must be added to `GameStateJS` explicitly
+                        GameState::PlayerOneWon => GameStateJS::PlayerOneWon,
+                        GameState::PlayerTwoWon => GameStateJS::PlayerTwoWon,
+                    },
+                    events: outcome.events,
                 }).unwrap())
             },
             Err(e) => Err(e.into())
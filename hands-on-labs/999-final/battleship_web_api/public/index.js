@@ -40,6 +40,11 @@ async function shoot(location) {
         body: JSON.stringify(location)
     });
 
+    // Shots rejected by the rules (e.g. after the game is over) do not change the board
+    if (!response.ok) {
+        return;
+    }
+
     const responseObj = await response.json();
     const boardContent = responseObj.board;
     let ix = 0;
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, sync::RwLock};
 
-use battleship_game_logic::{BoardIndex, SinglePlayerGame};
+use battleship_game_logic::{BoardIndex, GameEvent, ShotError, SinglePlayerGame};
 use uuid::Uuid;
 
 /*
@@ -34,7 +34,7 @@ mockall::mock! {
     pub GameRepository {
         pub fn get_by_id(&self, id: &ID) -> Option<Game>;
         pub fn add(&self, player: String) -> Game;
-        pub fn shoot(&self, id: &ID, location: BoardIndex) -> Option<Game>;
+        pub fn shoot(&self, id: &ID, location: BoardIndex) -> Option<Result<(Game, Vec<GameEvent>), ShotError>>;
     }
 }
 
@@ -59,13 +59,13 @@ impl GameRepository {
         new_game
     }
 
+    // Note: Returns `None` if there is no game with the given id and an error if the rules reject the shot
     #[allow(dead_code)]
-    pub fn shoot(&self, id: &ID, location: BoardIndex) -> Option<Game> {
+    pub fn shoot(&self, id: &ID, location: BoardIndex) -> Option<Result<(Game, Vec<GameEvent>), ShotError>> {
         let mut map = self.games.write().unwrap();
         let game = map.get_mut(id)?;
 
-        game.game.shoot(location);
-        Some(game.clone())
+        Some(game.game.try_shoot(location).map(|outcome| (game.clone(), outcome.events)))
     }
 
     #[allow(dead_code)]
@@ -45,7 +45,10 @@ struct GameResponse {
     id: Uuid,
     player: String,
     board: String,
-    game_status: u8
+    game_status: u8,
+    /// Describes what the last shot did (only set in response to a shot)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    events: Vec<String>,
 }
 
 impl GameResponse {
@@ -56,6 +59,7 @@ impl GameResponse {
             game.player.clone(),
             board: game.game.shooting_board_str(),
             game_status: game.game.game_state() as u8,
+            events: Vec::new(),
         }
     }
 }
@@ -72,7 +76,9 @@ enum ShotResult {
     #[response(status = 404)]
     NotFound(&'static str),
     #[response(status = 400)]
-    BadRequest(&'static str)
+    BadRequest(&'static str),
+    #[response(status = 422)]
+    InvalidShot(String),
 }
 
 #[post("/games/<id>/shoot", format = "json", data = "<location>")]
@@ -80,7 +86,11 @@ fn shoot(id: ID, location: Json<&str>, games_state: &State<GameRepository>) -> S
     match BoardIndex::from_str(location.as_ref()) {
         Ok(location) => {
             match games_state.shoot(&id, location) {
-                Some(game) => ShotResult::Success(Some(Json(GameResponse::from_game(&game)))),
+                Some(Ok((game, events))) => ShotResult::Success(Some(Json(GameResponse {
+                    events: events.iter().map(|e| e.to_string()).collect(),
+                    ..GameResponse::from_game(&game)
+                }))),
+                Some(Err(e)) => ShotResult::InvalidShot(e.to_string()),
                 None => ShotResult::NotFound("No game found with given id"),
             }
             
@@ -99,10 +109,10 @@ fn rocket() -> _ {
 
 #[cfg(test)]
 mod test {
-    use crate::{AddGameDto, game_repository::{Game, MockGameRepository}, get_game, start_game};
+    use crate::{AddGameDto, game_repository::{Game, MockGameRepository}, get_game, shoot, start_game};
     
     use super::rocket;
-    use battleship_game_logic::SinglePlayerGame;
+    use battleship_game_logic::{GameState, ShotError, SinglePlayerGame};
     use rocket::{http::Status, local::blocking::Client};
     use uuid::Uuid;
     
@@ -147,4 +157,19 @@ mod test {
         let response = client.get("/games/822c594e-b5ee-4ca5-ae3b-86d8bb97b43a").dispatch();
         assert_eq!(response.status(), Status::NotFound);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn shoot_rejected() {
+        let mut repo: MockGameRepository = MockGameRepository::default();
+        repo.expect_shoot().return_const(Some(Err(ShotError::GameFinished(GameState::AllShipsSunken))));
+
+        let r = rocket::build()
+            .manage( repo)
+            .mount("/", routes![shoot]);
+
+        let client = Client::tracked(r).unwrap();
+        let response = client.post("/games/822c594e-b5ee-4ca5-ae3b-86d8bb97b43a/shoot").json(&"A1").dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+        assert_eq!(Some(ShotError::GameFinished(GameState::AllShipsSunken).to_string()), response.into_string());
+    }
+}
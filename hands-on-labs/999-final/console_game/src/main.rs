@@ -1,10 +1,16 @@
-use battleship_game_logic::{BattleshipBoardContent, BoardFiller, SquareContent, random_placer};
+use std::{convert::TryFrom, fs::File, io::BufWriter, path::PathBuf, time::{SystemTime, UNIX_EPOCH}};
+
+use battleship_game_logic::{
+    BattleshipBoardContent, BimaruPuzzle, BimaruSolution, BoardFiller, BIMARU_FLEET, GameRules, HuntTargetStrategy, ProbabilityDensityStrategy, RandomStrategy,
+    ShipPlacer, ShooterFactory, ShotDistribution, Simulation, SquareContent, backtracking_placer, random_placer, write_csv,
+};
 use structopt::StructOpt;
 
 /*
     Learnings in this module:
 
     * Build a CLI with Rust
+    * Mapping command line arguments to functions
 
     Recommended readings for this module:
 
@@ -17,14 +23,48 @@ enum Command {
     SquareContent,
     Board {
         #[structopt(short, long, help = "Indicates whether the board should be filled")]
-        fill: bool
+        fill: bool,
+        #[structopt(short, long, default_value = "10", help = "Number of squares per row and column")]
+        side_length: usize,
+        #[structopt(long, help = "Seed for reproducible ship placement")]
+        seed: Option<u64>,
+    },
+    /// Plays many games automatically and reports how many shots were needed to win
+    Simulate {
+        #[structopt(short = "n", long, default_value = "1000", help = "Number of games")]
+        games: usize,
+        #[structopt(long, default_value = "backtracking", possible_values = &["random", "backtracking"])]
+        placer: String,
+        #[structopt(long, default_value = "hunt-target", possible_values = &["random", "hunt-target", "probability-density"])]
+        shooter: String,
+        #[structopt(short, long, default_value = "1", help = "Number of worker threads")]
+        threads: usize,
+        #[structopt(long, help = "Seed for reproducible runs")]
+        seed: Option<u64>,
+        #[structopt(long, parse(from_os_str), help = "File to write the results of all games to")]
+        csv: Option<PathBuf>,
+        #[structopt(long, default_value = "5", help = "Number of shots per histogram bar")]
+        bucket_width: usize,
+    },
+    /// Generates a battleship solitaire (Bimaru) puzzle with a unique solution
+    Bimaru {
+        #[structopt(short, long, default_value = "10", help = "Number of squares per row and column")]
+        side_length: usize,
+        #[structopt(long, help = "Seed for reproducible puzzles")]
+        seed: Option<u64>,
+        #[structopt(long, help = "Indicates whether the solution should be printed, too")]
+        solution: bool,
     },
 }
 
 fn main() {
     match Command::from_args() {
         Command::SquareContent => square_content(),
-        Command::Board { fill } => board(fill),
+        Command::Board { fill, side_length, seed } => board(fill, side_length, seed),
+        Command::Simulate { games, placer, shooter, threads, seed, csv, bucket_width } => {
+            simulate(games, &placer, &shooter, threads, seed, csv, bucket_width)
+        }
+        Command::Bimaru { side_length, seed, solution } => bimaru(side_length, seed, solution),
     }
 }
 
@@ -32,14 +72,91 @@ fn square_content() {
     let content = SquareContent::default();
     println!("Debug: {:?}", content);
     
-    let content: SquareContent = '~'.into();
+    let content = SquareContent::try_from('~').unwrap();
     println!("Parsed: {:?}", content);
     println!("Parsed Display: {}", char::from(content));
 }
 
-fn board(fill: bool) {
-    let mut board = BattleshipBoardContent::new_initialized(SquareContent::Water);
-    if fill { board.fill(&[5, 4, 3, 3, 2], random_placer); }
+fn board(fill: bool, side_length: usize, seed: Option<u64>) {
+    let mut board = BattleshipBoardContent::new_initialized_with_side_length(SquareContent::Water, side_length);
+    let result = match (fill, seed) {
+        (true, Some(seed)) => board.fill_with_seed(&[5, 4, 3, 3, 2], backtracking_placer, seed),
+        (true, None) => board.fill(&[5, 4, 3, 3, 2], backtracking_placer),
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        println!("Cannot fill board: {}", e);
+        return;
+    }
 
     println!("Filled board:\n{}", board);
 }
+
+fn simulate(games: usize, placer: &str, shooter: &str, threads: usize, seed: Option<u64>, csv: Option<PathBuf>, bucket_width: usize) {
+    // Note: structopt only accepts the possible values listed above
+    let placer: ShipPlacer = match placer {
+        "random" => random_placer,
+        _ => backtracking_placer,
+    };
+    let shooter: ShooterFactory = match shooter {
+        "random" => |_, seed| Box::new(RandomStrategy::with_seed(seed)),
+        "probability-density" => |rules, _| Box::new(ProbabilityDensityStrategy::new(rules)),
+        _ => |_, seed| Box::new(HuntTargetStrategy::with_seed(seed)),
+    };
+
+    // Note: The seed is printed, so every run can be repeated
+    let seed = seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64);
+    println!("Seed: {}", seed);
+
+    let simulation = Simulation { threads, ..Simulation::new(GameRules::default(), placer, shooter, games, seed) };
+    let outcomes = match simulation.run() {
+        Ok(outcomes) => outcomes,
+        Err(e) => {
+            println!("Cannot place ships: {}", e);
+            return;
+        }
+    };
+
+    let distribution = ShotDistribution::new(&outcomes);
+    println!("Games won: {} of {}", distribution.len(), outcomes.len());
+    if let (Some(mean), Some(median)) = (distribution.mean(), distribution.median()) {
+        println!("Shots to win: min {}, mean {:.2}, median {}, max {}", distribution.min().unwrap(), mean, median, distribution.max().unwrap());
+        for percent in [10.0, 25.0, 75.0, 90.0, 99.0] {
+            println!("  {:>2}th percentile: {}", percent, distribution.percentile(percent).unwrap());
+        }
+
+        let histogram = distribution.histogram(bucket_width);
+        let largest = histogram.iter().map(|(_, count)| *count).max().unwrap_or(1);
+        for (shots, count) in histogram {
+            println!("{:>4}-{:<4} {:>7} {}", shots, shots + bucket_width.max(1) - 1, count, "#".repeat(count * 50 / largest));
+        }
+    }
+
+    if let Some(path) = csv {
+        let result = File::create(&path).and_then(|file| write_csv(&outcomes, &mut BufWriter::new(file)));
+        match result {
+            Ok(()) => println!("Results written to {}", path.display()),
+            Err(e) => println!("Cannot write {}: {}", path.display(), e),
+        }
+    }
+}
+
+fn bimaru(side_length: usize, seed: Option<u64>, solution: bool) {
+    let seed = seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos() as u64);
+    println!("Seed: {}", seed);
+
+    let puzzle = match BimaruPuzzle::generate(side_length, BIMARU_FLEET, seed) {
+        Ok(puzzle) => puzzle,
+        Err(e) => {
+            println!("Cannot place ships: {}", e);
+            return;
+        }
+    };
+
+    println!("Fleet: {:?}\n\n{}", puzzle.ship_lengths(), puzzle);
+    if solution {
+        if let BimaruSolution::Unique(board) = puzzle.solve() {
+            println!("Solution:\n{}", board);
+        }
+    }
+}